@@ -0,0 +1,279 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use colored::Colorize;
+
+/// Name of the answers file inside the input directory
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+pub fn answers_path(input_dir: &Path) -> PathBuf {
+    input_dir.join(ANSWERS_FILE)
+}
+
+/// compares an answer to the expected one, ignoring surrounding whitespace
+/// (multi-line answers like the day 13 code start with a line break)
+pub fn answer_matches(expected: &str, answer: &str) -> bool {
+    expected.trim() == answer.trim()
+}
+
+/// The result of comparing an answer to the known one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong {
+        expected: String,
+    },
+    /// there is no known answer to compare to
+    Unknown,
+}
+
+impl Verdict {
+    pub fn of(expected: Option<&str>, answer: Option<&str>) -> Verdict {
+        match (expected, answer) {
+            (Some(expected), Some(answer)) if answer_matches(expected, answer) => Verdict::Correct,
+            (Some(expected), _) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+            (None, _) => Verdict::Unknown,
+        }
+    }
+    pub fn print(&self) {
+        match self {
+            Verdict::Correct => println!("\t{}", "✓ correct".green()),
+            Verdict::Wrong { expected } => {
+                println!("\t{} {}", "✗ wrong, expected".red(), expected.red())
+            }
+            Verdict::Unknown => println!("\t{}", "? unknown".yellow()),
+        }
+    }
+}
+
+/// Known answers, keyed by day and part.
+///
+/// The file has one `day part answer` line per answer, empty lines and
+/// lines starting with `#` are ignored. Line breaks inside an answer are
+/// written as `\n` (and backslashes as `\\`):
+/// ```text
+/// # day part answer
+/// 1 1 1292
+/// 13 2 #..#\n#..#
+/// ```
+/// Answers the server rejected are kept on lines starting with `wrong`,
+/// like `wrong 1 2 1234`. Until when the server takes no answers after
+/// submitting too often is kept as a unix time, like `wait 1638334800`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(usize, u8), String>,
+    rejected: BTreeSet<(usize, u8, String)>,
+    /// when the server takes answers again
+    wait_until: Option<SystemTime>,
+}
+
+impl Answers {
+    pub fn parse(content: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(time) = line.strip_prefix("wait ") {
+                let seconds = time
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("line {}: invalid time '{}'", i + 1, time))?;
+                answers.wait_until(UNIX_EPOCH + Duration::from_secs(seconds));
+                continue;
+            }
+            let (rejected, line) = match line.strip_prefix("wrong ") {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let mut fields = line.splitn(3, ' ');
+            let (day, part, answer) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(day), Some(part), Some(answer)) => (day, part, answer),
+                _ => return Err(format!("line {}: expected 'day part answer'", i + 1)),
+            };
+            let day = day
+                .parse()
+                .map_err(|_| format!("line {}: invalid day '{}'", i + 1, day))?;
+            let part = match part {
+                "1" => 1,
+                "2" => 2,
+                _ => return Err(format!("line {}: invalid part '{}'", i + 1, part)),
+            };
+            match rejected {
+                true => answers.reject(day, part, &unescape(answer)),
+                false => answers.insert(day, part, &unescape(answer)),
+            }
+        }
+        Ok(answers)
+    }
+
+    /// loads the answers from `path`, a missing file contains no answers
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                Answers::parse(&content).map_err(|err| format!("{}: {}", path.display(), err))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(format!("could not read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: usize, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: usize, part: u8, answer: &str) {
+        self.answers.insert((day, part), answer.trim().to_string());
+    }
+
+    /// remembers an answer the server said is wrong
+    pub fn reject(&mut self, day: usize, part: u8, answer: &str) {
+        self.rejected.insert((day, part, answer.trim().to_string()));
+    }
+
+    pub fn is_rejected(&self, day: usize, part: u8, answer: &str) -> bool {
+        self.rejected
+            .contains(&(day, part, answer.trim().to_string()))
+    }
+
+    /// remembers that the server takes no answers before `deadline`
+    pub fn wait_until(&mut self, deadline: SystemTime) {
+        self.wait_until = Some(deadline);
+    }
+
+    /// how long the server still takes no answers after `now`
+    pub fn wait_left(&self, now: SystemTime) -> Option<Duration> {
+        let left = self.wait_until?.duration_since(now).ok()?;
+        (!left.is_zero()).then_some(left)
+    }
+
+    pub fn check(&self, day: usize, part: u8, answer: Option<&str>) -> Verdict {
+        Verdict::of(self.get(day, part), answer)
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# day part answer")?;
+        for ((day, part), answer) in self.answers.iter() {
+            writeln!(f, "{} {} {}", day, part, escape(answer))?;
+        }
+        for (day, part, answer) in self.rejected.iter() {
+            writeln!(f, "wrong {} {} {}", day, part, escape(answer))?;
+        }
+        if let Some(deadline) = self.wait_until {
+            // rounded up, so the deadline is never earlier than the server's
+            let time = deadline.duration_since(UNIX_EPOCH).unwrap_or_default();
+            writeln!(f, "wait {}", time.as_millis().div_ceil(1000))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut result = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                result.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answers, Verdict};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn roundtrip() {
+        let mut answers = Answers::default();
+        answers.insert(13, 2, "\n#..#\n#\\.#");
+        answers.insert(1, 1, "1292");
+        let content = answers.to_string();
+        assert_eq!(
+            content,
+            "# day part answer\n1 1 1292\n13 2 #..#\\n#\\\\.#\n"
+        );
+        assert_eq!(Answers::parse(&content), Ok(answers));
+    }
+
+    #[test]
+    fn rejected_answers() {
+        let mut answers = Answers::default();
+        answers.insert(2, 1, "150");
+        answers.reject(2, 1, "25 ");
+        answers.reject(2, 1, "1500");
+        let content = answers.to_string();
+        assert_eq!(
+            content,
+            "# day part answer\n2 1 150\nwrong 2 1 1500\nwrong 2 1 25\n"
+        );
+        let answers = Answers::parse(&content).unwrap();
+        assert!(answers.is_rejected(2, 1, "25"));
+        assert!(!answers.is_rejected(2, 2, "25"));
+        assert!(!answers.is_rejected(2, 1, "150"));
+        assert_eq!(answers.len(), 1);
+    }
+
+    #[test]
+    fn rate_limit() {
+        let mut answers = Answers::default();
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        assert_eq!(answers.wait_left(now), None);
+        answers.wait_until(now + Duration::from_millis(82_500));
+        let answers = Answers::parse(&answers.to_string()).unwrap();
+        assert_eq!(answers.to_string(), "# day part answer\nwait 1083\n");
+        assert_eq!(answers.wait_left(now), Some(Duration::from_secs(83)));
+        assert_eq!(answers.wait_left(now + Duration::from_secs(83)), None);
+        assert!(Answers::parse("wait soon").is_err());
+    }
+
+    #[test]
+    fn verdicts() {
+        let answers = Answers::parse("# comment\n2 1 150\n").unwrap();
+        assert_eq!(answers.check(2, 1, Some("150")), Verdict::Correct);
+        assert_eq!(
+            answers.check(2, 1, Some("25")),
+            Verdict::Wrong {
+                expected: "150".into()
+            }
+        );
+        assert_eq!(answers.check(2, 2, Some("900")), Verdict::Unknown);
+        assert!(Answers::parse("2 3 150").is_err());
+        assert!(Answers::parse("wrong 2").is_err());
+        assert!(Answers::parse("2 1").is_err());
+    }
+}
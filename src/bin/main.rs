@@ -1,5 +1,165 @@
-use AdventOfCode2021::run;
+use std::process::exit;
+
+use colored::Colorize;
+use AdventOfCode2021::{
+    cli::{parse_args, Command, DEFAULT_TIMEOUT, USAGE},
+    client::{fetch_input, Fetched, Outcome},
+    config::{resolve_input_dir, Config},
+    dashboard::dashboard,
+    export,
+    fixtures::examples_dir,
+    gen, list, pool, registry, run,
+    scaffold::{crate_root, new_day, Scaffold},
+    server::Server,
+    submit, visualize, watch,
+};
+
+fn load_config() -> Config {
+    Config::load().unwrap_or_else(|err| {
+        eprintln!("Invalid config: {}", err);
+        exit(2);
+    })
+}
 
 fn main() {
-    run();
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            exit(2);
+        }
+    };
+    let registry = registry();
+    match command {
+        Command::Run(options) => {
+            if let Some(days) = &options.days {
+                if !days.clone().any(|day| registry.get(day).is_some()) {
+                    match days.start() == days.end() {
+                        true => eprintln!("Day {} is not registered", days.start()),
+                        false => eprintln!(
+                            "No day from {} to {} is registered",
+                            days.start(),
+                            days.end()
+                        ),
+                    }
+                    exit(1);
+                }
+            }
+            let config = load_config();
+            options.color.apply();
+            let input_dir = resolve_input_dir(options.input_dir.as_deref(), &config);
+            if options.dashboard {
+                if let Err(err) = dashboard(&registry, &options, &input_dir) {
+                    eprintln!("Could not show the dashboard: {}", err);
+                    exit(1);
+                }
+                return;
+            }
+            if let Some(path) = &options.export {
+                if let Err(err) = export(&registry, &options, &input_dir) {
+                    eprintln!("{}", err);
+                    exit(1);
+                }
+                println!("Wrote {}", path.display());
+                return;
+            }
+            if options.visualize {
+                if let Err(err) = visualize(&registry, &options, &input_dir) {
+                    eprintln!("{}", err);
+                    exit(1);
+                }
+                return;
+            }
+            match options.watch {
+                true => watch(&registry, &options, &input_dir, &examples_dir()),
+                false => run(&registry, &options, &input_dir),
+            }
+        }
+        Command::NewDay { day, input_dir } => {
+            if registry.get(day).is_some() {
+                eprintln!("Day {} is already registered", day);
+                exit(1);
+            }
+            let config = load_config();
+            let input_dir = resolve_input_dir(input_dir.as_deref(), &config);
+            let scaffold = Scaffold::new(&crate_root(), &input_dir, day);
+            match new_day(&scaffold, day) {
+                Ok(written) => {
+                    for path in written {
+                        println!("Wrote {}", path.display());
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Could not create day {}: {}", day, err);
+                    exit(1);
+                }
+            }
+        }
+        Command::Fetch { day, input_dir } => {
+            let config = load_config();
+            let input_dir = resolve_input_dir(input_dir.as_deref(), &config);
+            match fetch_input(&config, &input_dir, day) {
+                Ok(Fetched::Cached(path)) => {
+                    println!(
+                        "{} already exists, not downloading it again",
+                        path.display()
+                    )
+                }
+                Ok(Fetched::Downloaded(path)) => println!("Wrote {}", path.display()),
+                Err(err) => {
+                    eprintln!("Could not fetch the input of day {}: {}", day, err);
+                    exit(1);
+                }
+            }
+        }
+        Command::Submit {
+            day,
+            part,
+            input_dir,
+        } => {
+            let config = load_config();
+            let input_dir = resolve_input_dir(input_dir.as_deref(), &config);
+            match submit(&registry, &config, &input_dir, day, part) {
+                Ok((answer, Outcome::Correct)) => {
+                    println!("{} {}", answer, "✓ correct, recorded it".green())
+                }
+                Ok((answer, outcome)) => {
+                    let message = match outcome.is_wrong() {
+                        true => format!("✗ {}", outcome).red(),
+                        false => format!("? {}", outcome).yellow(),
+                    };
+                    println!("{} {}", answer, message);
+                    exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Could not submit day {} part {}: {}", day, part, err);
+                    exit(1);
+                }
+            }
+        }
+        Command::Gen { day, seed, size } => {
+            let size = size.or_else(|| gen::default_size(day));
+            match size.and_then(|size| gen::generate(day, seed, size)) {
+                Some(input) => println!("{}", input),
+                None => {
+                    eprintln!("There is no generator for day {}", day);
+                    exit(1);
+                }
+            }
+        }
+        Command::Serve { port } => {
+            match Server::bind(&registry, port, Some(DEFAULT_TIMEOUT), pool::default_jobs()) {
+                Ok(server) => {
+                    println!("Listening on http://127.0.0.1:{} (Ctrl+C to stop)", port);
+                    server.run()
+                }
+                Err(err) => {
+                    eprintln!("Could not listen on port {}: {}", port, err);
+                    exit(1);
+                }
+            }
+        }
+        Command::List => list(&registry),
+        Command::Help => println!("{}", USAGE),
+    }
 }
@@ -0,0 +1,627 @@
+use std::{ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use crate::{
+    export::{ImageFormat, Palette},
+    pool,
+    render::Format,
+    server::DEFAULT_PORT,
+    terminal::ColorChoice,
+    timing::Timing,
+};
+
+pub const USAGE: &str = "\
+Usage: aoc [OPTIONS]
+       aoc new-day <N> [--input-dir <PATH>]
+       aoc fetch <N> [--input-dir <PATH>]
+       aoc submit <N> <PART> [--input-dir <PATH>]
+       aoc gen <N> [--seed <S>] [--size <K>]
+       aoc serve [--port <P>]
+
+Commands:
+    new-day <N>         create src/dayN.rs with a template solution, an empty
+                        example and input and register the day
+    fetch <N>           download the input of day N into the input directory,
+                        using the session token from $AOC_SESSION or session
+                        in aoc.conf, existing inputs are never downloaded again
+    submit <N> <PART>   solve a part of day N and submit its answer, correct
+                        answers are stored in answers.txt, answers known to be
+                        wrong or right are not submitted again
+    gen <N>             print a random input for day N, the same seed (default
+                        0) always gives the same input, what the size counts
+                        depends on the day (lines, boards, grid columns, ...)
+    serve               answer HTTP requests on localhost (port 2021 by
+                        default): GET /days lists the days, POST /day/N solves
+                        day N on the input in the body and returns JSON
+
+Options:
+    --day <N>           only run day N
+    --days <A..B>       run all days from A to B (both included)
+    --part <1|2>        only run the given part
+    --input <PATH|->    read the input from PATH (or stdin for '-') instead of
+                        inputs/dayN.txt, requires a single day
+    --example           run the days on the example of their puzzle description
+                        and compare the answers to the expected ones
+    --record            store the answers in answers.txt of the input directory
+                        as the new known answers
+    --time              print how long parsing and each part take
+    --bench <N>         run every step N times and print the min, median and
+                        max duration
+    --format <FORMAT>   output format: text (default), json (one object per
+                        line), csv or markdown
+    --color <WHEN>      color the output: auto (default, only on a terminal and
+                        if $NO_COLOR is not set), always or never
+    --parallel          solve the days in parallel, using all cores
+    --jobs <N>          solve the days in parallel on N threads
+    --timeout <SECS>    give up on a day after SECS seconds (default 60, 0 for
+                        no limit)
+    --watch             re-run the day whenever its input or its example
+                        changes, requires a single day
+    --dashboard         show the whole calendar full-screen, with the state of
+                        every day and keys to re-run a day or all of them
+    --visualize         animate the computation of the day step by step in the
+                        terminal (days 5, 9, 11 and 13), requires a single day
+    --export <PATH>     write the steps of --visualize to an animated .gif, or
+                        the last step to a .png or .ppm image
+    --palette <COLORS>  colors of the exported images: dark (default), light,
+                        gray or four hex colors background,foreground,focus,
+                        marked like 000000,ffffff,ffd700,0099cc
+    --cell-size <PX>    draw every cell of the exported images PX pixels wide
+                        (default up to 8, smaller for large grids)
+    --input-dir <PATH>  directory containing the dayN.txt files, also of
+                        new-day, fetch and submit, defaults to $AOC_INPUT_DIR,
+                        input_dir in aoc.conf or inputs/
+    --list              list all registered days
+    -h, --help          print this message";
+
+/// where the puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `inputs/dayN.txt`
+    Default,
+    File(PathBuf),
+    Stdin,
+    /// the example from the puzzle description
+    Example,
+}
+
+/// Time each day may take before it is reported as timed out
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOptions {
+    /// `None` runs every registered day
+    pub days: Option<RangeInclusive<usize>>,
+    /// `None` runs both parts
+    pub part: Option<u8>,
+    pub input: InputSource,
+    /// `None` resolves the directory from the environment and config
+    pub input_dir: Option<PathBuf>,
+    /// store the answers as the new known answers
+    pub record: bool,
+    pub timing: Timing,
+    pub format: Format,
+    pub color: ColorChoice,
+    /// number of threads the days are solved on
+    pub jobs: usize,
+    /// time after which a day is reported as timed out, `None` waits forever
+    pub timeout: Option<Duration>,
+    /// re-run whenever the input changes
+    pub watch: bool,
+    /// show the full-screen dashboard instead of printing the results
+    pub dashboard: bool,
+    /// animate the visualization of the day instead of printing the results
+    pub visualize: bool,
+    /// write the visualization of the day to this image instead of printing the results
+    pub export: Option<PathBuf>,
+    pub palette: Palette,
+    /// pixels per cell of the exported image, `None` picks one fitting the grid
+    pub cell_size: Option<usize>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            days: None,
+            part: None,
+            input: InputSource::Default,
+            input_dir: None,
+            record: false,
+            timing: Timing::Off,
+            format: Format::Text,
+            color: ColorChoice::Auto,
+            jobs: 1,
+            timeout: Some(DEFAULT_TIMEOUT),
+            watch: false,
+            dashboard: false,
+            visualize: false,
+            export: None,
+            palette: Palette::default(),
+            cell_size: None,
+        }
+    }
+}
+
+impl RunOptions {
+    pub fn runs_day(&self, day: usize) -> bool {
+        match &self.days {
+            Some(days) => days.contains(&day),
+            None => true,
+        }
+    }
+    pub fn runs_part(&self, part: u8) -> bool {
+        match self.part {
+            Some(selected) => selected == part,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run(RunOptions),
+    List,
+    Help,
+    /// scaffold the module of a new day
+    NewDay {
+        day: usize,
+        input_dir: Option<PathBuf>,
+    },
+    /// download the input of a day
+    Fetch {
+        day: usize,
+        input_dir: Option<PathBuf>,
+    },
+    /// solve a part and submit its answer
+    Submit {
+        day: usize,
+        part: u8,
+        input_dir: Option<PathBuf>,
+    },
+    /// print a generated input
+    Gen {
+        day: usize,
+        seed: u64,
+        /// `None` uses the default size of the day
+        size: Option<usize>,
+    },
+    /// solve the inputs sent over HTTP
+    Serve {
+        port: u16,
+    },
+}
+
+fn parse_day(day: &str) -> Result<usize, String> {
+    match day.trim().parse::<usize>() {
+        Ok(day) if day > 0 => Ok(day),
+        _ => Err(format!("Invalid day: '{}'", day)),
+    }
+}
+
+fn parse_part(part: &str) -> Result<u8, String> {
+    match part {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("Invalid part: '{}'", part)),
+    }
+}
+
+/// parses ranges of the format `3..7` or `3..=7`, both including the end
+fn parse_day_range(range: &str) -> Result<RangeInclusive<usize>, String> {
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| format!("Invalid range of days: '{}'", range))?;
+    let end = end.strip_prefix('=').unwrap_or(end);
+    let (start, end) = (parse_day(start)?, parse_day(end)?);
+    if start > end {
+        return Err(format!("Range of days is empty: '{}'", range));
+    }
+    Ok(start..=end)
+}
+
+/// the `--input-dir <PATH>` the commands working on inputs take after their
+/// operands, like the runs do
+fn parse_input_dir(args: &mut impl Iterator<Item = String>) -> Result<Option<PathBuf>, String> {
+    let mut input_dir = None;
+    while let Some(arg) = args.next() {
+        let path = match arg.split_once('=') {
+            Some(("--input-dir", path)) => path.to_string(),
+            _ if arg == "--input-dir" => args
+                .next()
+                .ok_or_else(|| format!("Missing value for '{}'", arg))?,
+            _ => return Err(format!("Unknown argument: '{}'", arg)),
+        };
+        input_dir = Some(path.into());
+    }
+    Ok(input_dir)
+}
+
+/// parses the command line arguments (without the program name)
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "serve").is_some() {
+        let mut port = DEFAULT_PORT;
+        while let Some(flag) = args.next() {
+            if flag != "--port" {
+                return Err(format!("Unknown argument: '{}'", flag));
+            }
+            let value = args.next().unwrap_or_default();
+            port = match value.parse::<u16>() {
+                Ok(port) if port > 0 => port,
+                _ => return Err(format!("Invalid port: '{}'", value)),
+            };
+        }
+        return Ok(Command::Serve { port });
+    }
+    let subcommands = ["new-day", "fetch", "submit", "gen"];
+    if let Some(subcommand) = args.next_if(|arg| subcommands.contains(&arg.as_str())) {
+        let mut operand = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("Missing {} for '{}'", name, subcommand))
+        };
+        let day = parse_day(&operand("day")?)?;
+        let command = match subcommand.as_str() {
+            "fetch" => Command::Fetch {
+                day,
+                input_dir: parse_input_dir(&mut args)?,
+            },
+            "submit" => Command::Submit {
+                day,
+                part: parse_part(&operand("part")?)?,
+                input_dir: parse_input_dir(&mut args)?,
+            },
+            "gen" => {
+                let (mut seed, mut size) = (0, None);
+                while let Some(flag) = args.next() {
+                    if flag != "--seed" && flag != "--size" {
+                        return Err(format!("Unknown argument: '{}'", flag));
+                    }
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+                    match (flag.as_str(), value.parse::<u64>()) {
+                        ("--seed", Ok(value)) => seed = value,
+                        ("--size", Ok(value)) if value > 0 => size = Some(value as usize),
+                        _ => return Err(format!("Invalid value for '{}': '{}'", flag, value)),
+                    }
+                }
+                Command::Gen { day, seed, size }
+            }
+            _ => Command::NewDay {
+                day,
+                input_dir: parse_input_dir(&mut args)?,
+            },
+        };
+        if let Some(arg) = args.next() {
+            return Err(format!("Unknown argument: '{}'", arg));
+        }
+        return Ok(command);
+    }
+    while let Some(arg) = args.next() {
+        // allow both `--day 3` and `--day=3`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.into())),
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("Missing value for '{}'", flag))
+        };
+        match flag.as_str() {
+            "--day" => {
+                let day = parse_day(&value()?)?;
+                options.days = Some(day..=day);
+            }
+            "--days" => options.days = Some(parse_day_range(&value()?)?),
+            "--part" => options.part = Some(parse_part(&value()?)?),
+            "--input" => {
+                options.input = match value()?.as_str() {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(path.into()),
+                }
+            }
+            "--example" => options.input = InputSource::Example,
+            "--record" => options.record = true,
+            "--time" => options.timing = Timing::Once,
+            "--bench" => {
+                let runs = value()?;
+                options.timing = match runs.parse::<usize>() {
+                    Ok(runs) if runs > 0 => Timing::Bench(runs),
+                    _ => return Err(format!("Invalid number of runs: '{}'", runs)),
+                }
+            }
+            "--format" => options.format = value()?.parse()?,
+            "--color" => options.color = value()?.parse()?,
+            "--parallel" => options.jobs = pool::default_jobs(),
+            "--jobs" => {
+                let jobs = value()?;
+                options.jobs = match jobs.parse::<usize>() {
+                    Ok(jobs) if jobs > 0 => jobs,
+                    _ => return Err(format!("Invalid number of jobs: '{}'", jobs)),
+                }
+            }
+            "--timeout" => {
+                let seconds = value()?;
+                options.timeout = match seconds.parse::<f64>() {
+                    Ok(0.0) => None,
+                    Ok(seconds) if seconds > 0.0 && seconds.is_finite() => {
+                        Some(Duration::from_secs_f64(seconds))
+                    }
+                    _ => return Err(format!("Invalid timeout: '{}'", seconds)),
+                }
+            }
+            "--watch" => options.watch = true,
+            "--dashboard" => options.dashboard = true,
+            "--visualize" => options.visualize = true,
+            "--export" => {
+                let path = PathBuf::from(value()?);
+                ImageFormat::from_path(&path)?;
+                options.export = Some(path);
+            }
+            "--palette" => options.palette = value()?.parse()?,
+            "--cell-size" => {
+                let size = value()?;
+                options.cell_size = match size.parse::<usize>() {
+                    Ok(size) if (1..=64).contains(&size) => Some(size),
+                    _ => return Err(format!("Invalid cell size: '{}'", size)),
+                }
+            }
+            "--input-dir" => options.input_dir = Some(value()?.into()),
+            "--list" => return Ok(Command::List),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("Unknown argument: '{}'", flag)),
+        }
+    }
+    let single_day = matches!(&options.days, Some(days) if days.start() == days.end());
+    let needs_single_day = matches!(options.input, InputSource::File(_) | InputSource::Stdin);
+    if needs_single_day && !single_day {
+        return Err("--input can only be used together with --day".into());
+    }
+    if options.watch && !single_day {
+        return Err("--watch can only be used together with --day".into());
+    }
+    if options.watch && options.input == InputSource::Stdin {
+        return Err("--watch can not watch stdin".into());
+    }
+    if options.dashboard && (options.watch || options.input == InputSource::Stdin) {
+        return Err("--dashboard can not be combined with --watch or stdin".into());
+    }
+    if options.visualize && !single_day {
+        return Err("--visualize can only be used together with --day".into());
+    }
+    // the keys are read from stdin while animating
+    if options.visualize
+        && (options.watch || options.dashboard || options.input == InputSource::Stdin)
+    {
+        return Err("--visualize can not be combined with --watch, --dashboard or stdin".into());
+    }
+    if options.export.is_some() && !single_day {
+        return Err("--export can only be used together with --day".into());
+    }
+    if options.export.is_some() && (options.watch || options.dashboard || options.visualize) {
+        return Err("--export can not be combined with --watch, --dashboard or --visualize".into());
+    }
+    if options.record && options.input != InputSource::Default {
+        return Err("--record can only be used with the default inputs".into());
+    }
+    Ok(Command::Run(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_args, ColorChoice, Command, Format, InputSource, Palette, RunOptions, Timing,
+        DEFAULT_TIMEOUT,
+    };
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_runs_everything() {
+        assert_eq!(parse(&[]), Ok(Command::Run(RunOptions::default())));
+    }
+
+    #[test]
+    fn single_day_and_part() {
+        let command = parse(&["--day", "9", "--part=2", "--input", "-"]).unwrap();
+        assert_eq!(
+            command,
+            Command::Run(RunOptions {
+                days: Some(9..=9),
+                part: Some(2),
+                input: InputSource::Stdin,
+                input_dir: None,
+                record: false,
+                timing: Timing::Off,
+                format: Format::Text,
+                color: ColorChoice::Auto,
+                jobs: 1,
+                timeout: Some(DEFAULT_TIMEOUT),
+                watch: false,
+                dashboard: false,
+                visualize: false,
+                export: None,
+                palette: Palette::default(),
+                cell_size: None,
+            })
+        );
+    }
+
+    #[test]
+    fn day_ranges() {
+        for range in ["3..7", "3..=7"] {
+            match parse(&["--days", range]).unwrap() {
+                Command::Run(options) => {
+                    assert_eq!(options.days, Some(3..=7));
+                    assert!(options.runs_day(7) && !options.runs_day(8));
+                }
+                command => panic!("Expected a run command, got {:?}", command),
+            }
+        }
+        assert!(parse(&["--days", "7..3"]).is_err());
+        assert!(parse(&["--days", "7"]).is_err());
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(parse(&["--part", "3"]).is_err());
+        assert!(parse(&["--day"]).is_err());
+        assert!(parse(&["--frobnicate"]).is_err());
+        // an input file only makes sense for a single day
+        assert!(parse(&["--days", "1..2", "--input", "day1.txt"]).is_err());
+        // answers of the examples are not the answers of the puzzle
+        assert!(parse(&["--example", "--record"]).is_err());
+        assert!(parse(&["--bench", "0"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--color", "sometimes"]).is_err());
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["--timeout", "-1"]).is_err());
+        assert!(parse(&["--watch"]).is_err());
+        assert!(parse(&["--day", "3", "--watch", "--input", "-"]).is_err());
+        assert!(parse(&["--day", "3", "--watch", "--dashboard"]).is_err());
+        assert!(parse(&["--days", "9..11", "--visualize"]).is_err());
+        assert!(parse(&["--day", "9", "--visualize", "--input", "-"]).is_err());
+        assert!(parse(&["--day", "9", "--export", "day9.jpg"]).is_err());
+        assert!(parse(&["--day", "9", "--export", "day9.gif", "--visualize"]).is_err());
+        assert!(parse(&["--cell-size", "0"]).is_err());
+        assert!(parse(&["--palette", "rainbow"]).is_err());
+    }
+
+    #[test]
+    fn example() {
+        match parse(&["--days", "1..3", "--example"]).unwrap() {
+            Command::Run(options) => assert_eq!(options.input, InputSource::Example),
+            command => panic!("Expected a run command, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn export() {
+        let args = [
+            "--day=11",
+            "--export",
+            "day11.gif",
+            "--palette=light",
+            "--cell-size=4",
+        ];
+        match parse(&args).unwrap() {
+            Command::Run(options) => {
+                assert_eq!(options.export, Some("day11.gif".into()));
+                assert_eq!(options.palette, Palette::LIGHT);
+                assert_eq!(options.cell_size, Some(4));
+            }
+            command => panic!("Expected a run command, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn timing() {
+        for (args, timing) in [
+            (&["--time"][..], Timing::Once),
+            (&["--bench=5"], Timing::Bench(5)),
+        ] {
+            match parse(args).unwrap() {
+                Command::Run(options) => assert_eq!(options.timing, timing),
+                command => panic!("Expected a run command, got {:?}", command),
+            }
+        }
+    }
+
+    #[test]
+    fn new_day() {
+        assert_eq!(
+            parse(&["new-day", "17"]),
+            Ok(Command::NewDay {
+                day: 17,
+                input_dir: None
+            })
+        );
+        assert_eq!(
+            parse(&["new-day", "17", "--input-dir", "puzzles"]),
+            Ok(Command::NewDay {
+                day: 17,
+                input_dir: Some("puzzles".into())
+            })
+        );
+        assert!(parse(&["new-day"]).is_err());
+        assert!(parse(&["new-day", "17", "--day", "3"]).is_err());
+        assert!(parse(&["new-day", "17", "--input-dir"]).is_err());
+        assert_eq!(
+            parse(&["fetch", "3", "--input-dir=puzzles"]),
+            Ok(Command::Fetch {
+                day: 3,
+                input_dir: Some("puzzles".into())
+            })
+        );
+    }
+
+    #[test]
+    fn submit() {
+        assert_eq!(
+            parse(&["submit", "3", "2"]),
+            Ok(Command::Submit {
+                day: 3,
+                part: 2,
+                input_dir: None
+            })
+        );
+        assert_eq!(
+            parse(&["submit", "3", "2", "--input-dir", "puzzles"]),
+            Ok(Command::Submit {
+                day: 3,
+                part: 2,
+                input_dir: Some("puzzles".into())
+            })
+        );
+        assert_eq!(
+            parse(&["submit", "3"]),
+            Err("Missing part for 'submit'".into())
+        );
+        assert!(parse(&["submit", "3", "3"]).is_err());
+        assert!(parse(&["submit", "3", "1", "2"]).is_err());
+    }
+
+    #[test]
+    fn gen() {
+        assert_eq!(
+            parse(&["gen", "4"]),
+            Ok(Command::Gen {
+                day: 4,
+                seed: 0,
+                size: None
+            })
+        );
+        assert_eq!(
+            parse(&["gen", "4", "--size", "1000", "--seed", "7"]),
+            Ok(Command::Gen {
+                day: 4,
+                seed: 7,
+                size: Some(1000)
+            })
+        );
+        assert!(parse(&["gen", "4", "--size", "0"]).is_err());
+        assert!(parse(&["gen", "4", "--seed"]).is_err());
+        assert!(parse(&["gen", "4", "--day", "3"]).is_err());
+    }
+
+    #[test]
+    fn serve() {
+        assert_eq!(parse(&["serve"]), Ok(Command::Serve { port: 2021 }));
+        assert_eq!(
+            parse(&["serve", "--port", "8080"]),
+            Ok(Command::Serve { port: 8080 })
+        );
+        assert!(parse(&["serve", "--port"]).is_err());
+        assert!(parse(&["serve", "--port", "0"]).is_err());
+        assert!(parse(&["serve", "--day", "3"]).is_err());
+    }
+
+    #[test]
+    fn list() {
+        assert_eq!(parse(&["--list"]), Ok(Command::List));
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    answers::Answers,
+    config::{Config, BASE_URL_ENV, SESSION_ENV},
+    helper::input_path,
+};
+
+/// The server the puzzles are hosted on
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// The year of the calendar solved by this crate
+pub const YEAR: usize = 2021;
+const USER_AGENT: &str = concat!("AdventOfCode2021/", env!("CARGO_PKG_VERSION"));
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Talks to adventofcode.com (or a server pretending to be it)
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Client {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build(),
+        }
+    }
+
+    /// Takes the session token and base URL from `AOC_SESSION` and `AOC_BASE_URL`
+    /// or from the config, there is no default for the session.
+    pub fn from_config(config: &Config) -> Result<Client, String> {
+        let session = env::var(SESSION_ENV)
+            .ok()
+            .or_else(|| config.session.clone())
+            .filter(|session| !session.trim().is_empty())
+            .ok_or_else(|| {
+                format!(
+                    "no session token, set ${} or 'session' in the config",
+                    SESSION_ENV
+                )
+            })?;
+        let base_url = env::var(BASE_URL_ENV)
+            .ok()
+            .or_else(|| config.base_url.clone())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.into());
+        Ok(Client::new(&base_url, session.trim()))
+    }
+
+    /// the URL of a page of the day, `path` is appended to `/2021/day/N`
+    pub fn url(&self, day: usize, path: &str) -> String {
+        format!("{}/{}/day/{}{}", self.base_url, YEAR, day, path)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// downloads the puzzle input of a day
+    pub fn input(&self, day: usize) -> Result<String, String> {
+        let url = self.url(day, "/input");
+        let response = self.agent.get(&url).set("Cookie", &self.cookie()).call();
+        read_response(&url, response)
+    }
+
+    /// posts the answer of a part and tells what the server thought of it
+    pub fn submit(&self, day: usize, part: u8, answer: &str) -> Result<Outcome, String> {
+        let url = self.url(day, "/answer");
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+        let body = read_response(&url, response)?;
+        Outcome::parse(&body)
+            .ok_or_else(|| format!("unexpected response from {}: {}", url, article(&body)))
+    }
+}
+
+/// the body of a successful response, or an error describing what went wrong
+fn read_response(
+    url: &str,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, String> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|err| format!("could not read the response of {}: {}", url, err)),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            Err(format!("{} returned {}: {}", url, status, body.trim()))
+        }
+        // transport errors already name the URL
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// What the server said about a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// an answer was submitted too recently, `wait` is the time left if the
+    /// server told it
+    RateLimited {
+        wait: Option<Duration>,
+    },
+    /// the part was already solved, or the part before it is not yet
+    AlreadySolved,
+}
+
+impl Outcome {
+    /// reads the outcome from the page the server answers a submission with
+    pub fn parse(body: &str) -> Option<Outcome> {
+        let text = article(body);
+        let outcome = if text.contains("That's the right answer") {
+            Outcome::Correct
+        } else if text.contains("answer too recently") {
+            Outcome::RateLimited {
+                wait: parse_wait(&text),
+            }
+        } else if text.contains("not the right answer") {
+            match (text.contains("too high"), text.contains("too low")) {
+                (true, false) => Outcome::TooHigh,
+                (false, true) => Outcome::TooLow,
+                _ => Outcome::Wrong,
+            }
+        } else if text.contains("solving the right level") {
+            Outcome::AlreadySolved
+        } else {
+            return None;
+        };
+        Some(outcome)
+    }
+
+    /// whether the answer is known to be wrong afterwards
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::TooHigh => write!(f, "wrong, too high"),
+            Outcome::TooLow => write!(f, "wrong, too low"),
+            Outcome::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, wait {}s", wait.as_secs())
+            }
+            Outcome::RateLimited { wait: None } => write!(f, "rate limited"),
+            Outcome::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// the text of the `<article>` of a page (the whole page if there is none)
+/// without tags and with collapsed whitespace
+fn article(body: &str) -> String {
+    let start = body.find("<article").unwrap_or(0);
+    let end = body[start..]
+        .find("</article>")
+        .map_or(body.len(), |end| start + end);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in body[start..end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// reads the time left from "You have 1m 23s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (before, _) = text.split_once(" left to wait")?;
+    let (_, wait) = before.rsplit_once("You have ")?;
+    let mut seconds = 0;
+    for amount in wait.split_whitespace() {
+        let unit = amount.find(|c: char| !c.is_ascii_digit())?;
+        let value: u64 = amount[..unit].parse().ok()?;
+        seconds += value
+            * match &amount[unit..] {
+                "h" => 3600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// Submits an answer unless it is already known, and remembers what the server
+/// said about it in `answers`.
+///
+/// Answers known to be wrong are never submitted again. If a correct answer is
+/// known, only that one would be accepted, so nothing is submitted either.
+/// Nothing is submitted while the server still waits after a rate limit.
+pub fn submit_answer(
+    client: &Client,
+    answers: &mut Answers,
+    day: usize,
+    part: u8,
+    answer: &str,
+) -> Result<Outcome, String> {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err("the answer is empty".into());
+    }
+    // like the code of day 13, which has to be read off the grid
+    if answer.contains('\n') {
+        return Err("multi-line answers have to be read and submitted by hand".into());
+    }
+    if answers.is_rejected(day, part, answer) {
+        return Err(format!("{} is already known to be wrong", answer));
+    }
+    match answers.get(day, part) {
+        Some(known) if known == answer => {
+            return Err(format!("{} is already known to be correct", answer))
+        }
+        Some(known) => return Err(format!("the correct answer is known to be {}", known)),
+        None => (),
+    }
+    let now = SystemTime::now();
+    if let Some(left) = answers.wait_left(now) {
+        return Err(format!(
+            "an answer was submitted too recently, wait {}s",
+            left.as_millis().div_ceil(1000)
+        ));
+    }
+    let outcome = client.submit(day, part, answer)?;
+    match &outcome {
+        Outcome::Correct => answers.insert(day, part, answer),
+        outcome if outcome.is_wrong() => answers.reject(day, part, answer),
+        Outcome::RateLimited { wait: Some(wait) } => answers.wait_until(now + *wait),
+        _ => (),
+    }
+    Ok(outcome)
+}
+
+/// Where `fetch_input` got the input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// the input was already in the input directory
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Stores the input of a day in the input directory, downloading it only if
+/// it is not there yet.
+pub fn fetch_input(config: &Config, input_dir: &Path, day: usize) -> Result<Fetched, String> {
+    let path = input_path(input_dir, day);
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = Client::from_config(config)?.input(day)?;
+    fs::create_dir_all(input_dir)
+        .and_then(|_| fs::write(&path, input))
+        .map_err(|err| format!("could not write {}: {}", path.display(), err))?;
+    Ok(Fetched::Downloaded(path))
+}
+
+/// A minimal HTTP server answering requests with canned responses
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    /// serves one response per entry of `responses`, returning the base URL
+    /// and a handle resolving to the requests that were received
+    pub fn serve<B: Into<String>>(responses: Vec<(u16, B)>) -> (String, JoinHandle<Vec<String>>) {
+        let responses: Vec<(u16, String)> = responses
+            .into_iter()
+            .map(|(status, body)| (status, body.into()))
+            .collect();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8_lossy(&content));
+                requests.push(request);
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (base_url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fetch_input, stub, submit_answer, Client, Fetched, Outcome};
+    use crate::{answers::Answers, config::Config};
+    use std::{
+        env, fs, process,
+        time::{Duration, SystemTime},
+    };
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn fetch_and_cache() {
+        let (base_url, server) = stub::serve(vec![(200, "1\n2\n3\n")]);
+        let config = Config {
+            session: Some("secret".into()),
+            base_url: Some(base_url),
+            ..Config::default()
+        };
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("day3.txt");
+        assert_eq!(
+            fetch_input(&config, &dir, 3),
+            Ok(Fetched::Downloaded(path.clone()))
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        // the stub only answers once, so a second download would fail
+        assert_eq!(fetch_input(&config, &dir, 3), Ok(Fetched::Cached(path)));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2021/day/3/input HTTP/1.1"));
+        assert!(requests[0].contains("session=secret"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn server_errors() {
+        let (base_url, server) = stub::serve(vec![(404, "Not found")]);
+        let err = Client::new(&base_url, "secret").input(26).unwrap_err();
+        assert!(err.ends_with("/2021/day/26/input returned 404: Not found"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn outcomes() {
+        let parse = |message: &str| Outcome::parse(&page(message));
+        assert_eq!(
+            parse("That's the right answer! You are <em>one gold star</em> closer."),
+            Some(Outcome::Correct)
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too high. Please wait one minute."),
+            Some(Outcome::TooHigh)
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low."),
+            Some(Outcome::TooLow)
+        );
+        assert_eq!(
+            parse("That's not the right answer. If you're stuck, make sure you're using the full input data."),
+            Some(Outcome::Wrong)
+        );
+        assert_eq!(
+            parse("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait."),
+            Some(Outcome::RateLimited {
+                wait: Some(Duration::from_secs(83))
+            })
+        );
+        assert_eq!(
+            parse("You don't seem to be solving the right level.  Did you already complete it?"),
+            Some(Outcome::AlreadySolved)
+        );
+        assert_eq!(parse("Puzzle inputs differ by user."), None);
+    }
+
+    #[test]
+    fn submit_and_remember() {
+        let wrong = page("That's not the right answer; your answer is too low.");
+        let correct = page("That's the right answer!");
+        let (base_url, server) = stub::serve(vec![(200, wrong), (200, correct)]);
+        let client = Client::new(&base_url, "secret");
+        let mut answers = Answers::default();
+        assert_eq!(
+            submit_answer(&client, &mut answers, 2, 1, "25"),
+            Ok(Outcome::TooLow)
+        );
+        // the stub only answers twice, so resubmitting would fail differently
+        assert_eq!(
+            submit_answer(&client, &mut answers, 2, 1, "25\n"),
+            Err("25 is already known to be wrong".into())
+        );
+        assert_eq!(
+            submit_answer(&client, &mut answers, 2, 1, "150"),
+            Ok(Outcome::Correct)
+        );
+        assert_eq!(answers.get(2, 1), Some("150"));
+        assert!(answers.is_rejected(2, 1, "25"));
+        assert!(submit_answer(&client, &mut answers, 2, 1, "150").is_err());
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2021/day/2/answer HTTP/1.1"));
+        assert!(requests[0].contains("session=secret"));
+        assert!(requests[0].ends_with("level=1&answer=25"));
+        assert!(requests[1].ends_with("level=1&answer=150"));
+    }
+
+    #[test]
+    fn wait_after_a_rate_limit() {
+        let limited = page("You gave an answer too recently. You have 1m 23s left to wait.");
+        let (base_url, server) = stub::serve(vec![(200, limited)]);
+        let client = Client::new(&base_url, "secret");
+        let mut answers = Answers::default();
+        assert!(matches!(
+            submit_answer(&client, &mut answers, 2, 1, "25"),
+            Ok(Outcome::RateLimited { .. })
+        ));
+        // the stub only answers once, the second answer is not submitted
+        let err = submit_answer(&client, &mut answers, 2, 1, "150").unwrap_err();
+        assert!(err.starts_with("an answer was submitted too recently, wait 8"));
+        let answers = Answers::parse(&answers.to_string()).unwrap();
+        assert!(answers.wait_left(SystemTime::now()).is_some());
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable pointing to the config file
+pub const CONFIG_ENV: &str = "AOC_CONFIG";
+/// Environment variable overriding the input directory
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+/// Environment variable holding the session token of adventofcode.com
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// Environment variable overriding the server inputs are fetched from
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+/// Name of the config file looked for in the current directory
+pub const CONFIG_FILE: &str = "aoc.conf";
+
+/// Settings read from the config file.
+///
+/// The file consists of `key = value` lines, empty lines and lines
+/// starting with `#` are ignored:
+/// ```text
+/// # where the puzzle inputs are stored
+/// input_dir = /home/me/advent/inputs
+/// # the value of the session cookie, used to fetch the inputs
+/// session = 53616c7465645f5f...
+/// base_url = https://adventofcode.com
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub input_dir: Option<PathBuf>,
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn parse(content: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected 'key = value'", i + 1))?;
+            let value = value.trim();
+            match key.trim() {
+                "input_dir" => config.input_dir = Some(value.into()),
+                "session" => config.session = Some(value.into()),
+                "base_url" => config.base_url = Some(value.into()),
+                key => return Err(format!("line {}: unknown key '{}'", i + 1, key)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Loads the config from the file given by `AOC_CONFIG` or from
+    /// `aoc.conf` in the current directory.
+    ///
+    /// A missing file results in the default config, relative paths in
+    /// the config are resolved relative to the file.
+    pub fn load() -> Result<Config, String> {
+        let path = match env::var_os(CONFIG_ENV) {
+            Some(path) => PathBuf::from(path),
+            None if Path::new(CONFIG_FILE).is_file() => PathBuf::from(CONFIG_FILE),
+            None => return Ok(Config::default()),
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        let mut config =
+            Config::parse(&content).map_err(|err| format!("{}: {}", path.display(), err))?;
+        if let (Some(dir), Some(base)) = (&config.input_dir, path.parent()) {
+            config.input_dir = Some(base.join(dir));
+        }
+        Ok(config)
+    }
+}
+
+/// Determines the directory the inputs are loaded from.
+///
+/// In order of priority this is the directory passed on the command line,
+/// the `AOC_INPUT_DIR` environment variable, the `input_dir` of the config
+/// file, `inputs/` in the current directory and lastly an `inputs/`
+/// directory next to the executable or in one of its parent directories
+/// (so `target/release/aoc` finds the inputs of the repository).
+pub fn resolve_input_dir(flag: Option<&Path>, config: &Config) -> PathBuf {
+    if let Some(dir) = flag {
+        return dir.to_path_buf();
+    }
+    if let Some(dir) = env::var_os(INPUT_DIR_ENV) {
+        return dir.into();
+    }
+    if let Some(dir) = &config.input_dir {
+        return dir.clone();
+    }
+    let local = PathBuf::from("inputs");
+    if local.is_dir() {
+        return local;
+    }
+    env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.ancestors()
+                .skip(1)
+                .map(|dir| dir.join("inputs"))
+                .find(|dir| dir.is_dir())
+        })
+        .unwrap_or(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_input_dir, Config};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn parse_config() {
+        let config = Config::parse("# comment\n\n input_dir = /tmp/aoc \n").unwrap();
+        assert_eq!(config.input_dir, Some(PathBuf::from("/tmp/aoc")));
+        assert!(Config::parse("input_dir").is_err());
+        assert!(Config::parse("colour = blue").is_err());
+        let config = Config::parse("session = abc\nbase_url = http://localhost:8080").unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:8080"));
+    }
+
+    #[test]
+    fn flag_has_priority() {
+        let config = Config {
+            input_dir: Some("from/config".into()),
+            ..Config::default()
+        };
+        let dir = resolve_input_dir(Some(Path::new("from/flag")), &config);
+        assert_eq!(dir, PathBuf::from("from/flag"));
+    }
+}
@@ -0,0 +1,532 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread,
+    time::Duration,
+};
+
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, StyledContent, Stylize},
+    terminal::{self, Clear, ClearType},
+};
+
+use crate::{
+    answers::{answers_path, Answers, Verdict},
+    cli::{InputSource, RunOptions},
+    error::AocError,
+    solution::{Day, Registry},
+    solve_day,
+    terminal::FullScreen,
+    verdict, DayReport,
+};
+
+/// The days of the calendar
+const DAYS: usize = 25;
+
+/// How often the dashboard checks for key presses and finished days
+const TICK: Duration = Duration::from_millis(100);
+
+/// How far a day is implemented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Implementation {
+    Implemented,
+    /// registered, but a part is still a `todo!()`
+    Stubbed,
+    /// not registered
+    Missing,
+}
+
+/// What is known about the answer of a part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnswerState {
+    /// the part has not been run yet
+    Pending,
+    /// the answer is the known one
+    Verified,
+    /// there is no known answer to compare to
+    Unverified,
+    /// the answer is wrong or the part failed
+    Failing,
+    /// the input of the day could not be loaded
+    NoInput,
+}
+
+/// A day of the calendar as shown on the dashboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub day: usize,
+    pub implementation: Implementation,
+    pub parts: [AnswerState; 2],
+    /// how long parsing and both parts took on the last run
+    pub runtime: Option<Duration>,
+    pub running: bool,
+}
+
+/// whether a part failed because it is not written yet
+fn is_stub(err: &AocError) -> bool {
+    match err {
+        AocError::Panic { message, .. } => {
+            message.starts_with("not yet implemented") || message.starts_with("not implemented")
+        }
+        _ => false,
+    }
+}
+
+/// whether parsing or a part of a run hit a `todo!()`
+fn has_stubs(report: &DayReport) -> bool {
+    match &report.result {
+        Ok((_, parts)) => parts
+            .iter()
+            .any(|part| part.output.as_ref().is_err_and(is_stub)),
+        Err(err) => is_stub(err),
+    }
+}
+
+impl Row {
+    /// a registered day counts as implemented until a run hits a `todo!()`
+    fn new(day: usize, registered: bool) -> Row {
+        Row {
+            day,
+            implementation: match registered {
+                false => Implementation::Missing,
+                true => Implementation::Implemented,
+            },
+            parts: [AnswerState::Pending; 2],
+            runtime: None,
+            running: false,
+        }
+    }
+
+    /// a star for every verified part
+    pub fn stars(&self) -> usize {
+        let verified = self.parts.iter();
+        verified
+            .filter(|state| **state == AnswerState::Verified)
+            .count()
+    }
+
+    /// takes over the results of a run, `verdict` compares an answer of a part
+    fn update(
+        &mut self,
+        report: &DayReport,
+        verdict: impl Fn(u8, Option<&str>) -> Option<Verdict>,
+    ) {
+        self.running = false;
+        self.implementation = match has_stubs(report) {
+            true => Implementation::Stubbed,
+            false => Implementation::Implemented,
+        };
+        let (parse, parts) = match &report.result {
+            Ok(result) => result,
+            Err(AocError::Input(_)) => {
+                self.parts = [AnswerState::NoInput; 2];
+                self.runtime = None;
+                return;
+            }
+            Err(_) => {
+                self.parts = [AnswerState::Failing; 2];
+                self.runtime = None;
+                return;
+            }
+        };
+        let mut runtime = parse.median();
+        for part in parts {
+            runtime += part.samples.median();
+            self.parts[part.part as usize - 1] = match &part.output {
+                Ok(output) => match verdict(part.part, output.answer_value().as_deref()) {
+                    Some(Verdict::Correct) => AnswerState::Verified,
+                    Some(Verdict::Wrong { .. }) => AnswerState::Failing,
+                    Some(Verdict::Unknown) | None => AnswerState::Unverified,
+                },
+                Err(_) => AnswerState::Failing,
+            };
+        }
+        self.runtime = Some(runtime);
+    }
+
+    /// takes over whether the run of a day without an input on its example
+    /// hit a `todo!()`
+    fn update_from_example(&mut self, example: &DayReport) {
+        if has_stubs(example) {
+            self.implementation = Implementation::Stubbed;
+        }
+    }
+
+    /// the text of the columns: day, status, both parts, runtime and stars
+    pub fn cells(&self) -> [String; 6] {
+        let status = match (self.running, self.implementation) {
+            (true, _) => "running",
+            (_, Implementation::Implemented) => "implemented",
+            (_, Implementation::Stubbed) => "stubbed",
+            (_, Implementation::Missing) => "missing",
+        };
+        let part = |state: AnswerState| match (self.implementation, state) {
+            (Implementation::Missing, _) => "",
+            (_, AnswerState::Pending) => "·",
+            (_, AnswerState::Verified) => "verified",
+            (_, AnswerState::Unverified) => "unverified",
+            (_, AnswerState::Failing) => "failing",
+            (_, AnswerState::NoInput) => "no input",
+        };
+        let runtime = match self.runtime {
+            Some(runtime) => format!("{:.2?}", runtime),
+            None => String::new(),
+        };
+        let stars = "★".repeat(self.stars()) + &"·".repeat(2 - self.stars());
+        [
+            self.day.to_string(),
+            status.into(),
+            part(self.parts[0]).into(),
+            part(self.parts[1]).into(),
+            runtime,
+            stars,
+        ]
+    }
+}
+
+/// The rows of the whole calendar and the selected day
+pub struct Dashboard {
+    pub rows: Vec<Row>,
+    pub selected: usize,
+}
+
+impl Dashboard {
+    pub fn new(registry: &Registry) -> Dashboard {
+        Dashboard {
+            rows: (1..=DAYS)
+                .map(|day| Row::new(day, registry.get(day).is_some()))
+                .collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn stars(&self) -> usize {
+        self.rows.iter().map(Row::stars).sum()
+    }
+
+    /// whether a run has not finished yet
+    fn running(&self) -> bool {
+        self.rows.iter().any(|row| row.running)
+    }
+
+    /// the lines of the dashboard for a terminal of `height` lines, scrolled so
+    /// the selected day is visible
+    fn lines(&self, height: usize) -> Vec<Vec<StyledContent<String>>> {
+        let title = format!(
+            "Advent of Code 2021   {} of {} stars",
+            self.stars(),
+            2 * DAYS
+        );
+        let header = ["Day", "Status", "Part 1", "Part 2", "Time", "Stars"];
+        let header = header.iter().zip(WIDTHS);
+        let header: String = header.map(|(cell, width)| pad(cell, width)).collect();
+        let mut lines = vec![vec![title.bold()], vec![format!("  {}", header).dim()]];
+        let state = |text: String, state: AnswerState| match state {
+            AnswerState::Verified => text.green(),
+            AnswerState::Unverified => text.yellow(),
+            AnswerState::Failing => text.red(),
+            AnswerState::Pending | AnswerState::NoInput => text.dim(),
+        };
+        let visible = height.saturating_sub(3).max(1);
+        let first = (self.selected + 1).saturating_sub(visible);
+        for (i, row) in self.rows.iter().enumerate().skip(first).take(visible) {
+            let marker = if i == self.selected { ">" } else { " " };
+            let [day, status, part1, part2, runtime, stars] = row.cells();
+            let status = pad(&status, WIDTHS[1]);
+            let status = match row.implementation {
+                _ if row.running => status.cyan(),
+                Implementation::Implemented => status.reset(),
+                Implementation::Stubbed => status.yellow(),
+                Implementation::Missing => status.dim(),
+            };
+            lines.push(vec![
+                format!("{} {}", marker, pad(&day, WIDTHS[0])).bold(),
+                status,
+                state(pad(&part1, WIDTHS[2]), row.parts[0]),
+                state(pad(&part2, WIDTHS[3]), row.parts[1]),
+                pad(&runtime, WIDTHS[4]).reset(),
+                stars.yellow(),
+            ]);
+        }
+        let keys = "↑/↓ select   enter/r run the day   a run all days   q quit";
+        lines.push(vec![keys.to_string().dim()]);
+        lines
+    }
+}
+
+/// the widths of the columns
+const WIDTHS: [usize; 6] = [4, 13, 12, 12, 12, 5];
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{:<width$}", text, width = width)
+}
+
+/// draws the dashboard over the whole screen
+fn draw(dashboard: &Dashboard) -> io::Result<()> {
+    let (_, height) = terminal::size()?;
+    let mut stdout = io::stdout();
+    queue!(stdout, Clear(ClearType::All))?;
+    for (y, line) in dashboard.lines(height as usize).into_iter().enumerate() {
+        queue!(stdout, MoveTo(0, y as u16))?;
+        for cell in line {
+            queue!(stdout, Print(cell))?;
+        }
+    }
+    stdout.flush()
+}
+
+/// the report of a day, and the report of a run on its example if the day
+/// has no input, so stubs are found without an input
+type Reports = (DayReport, Option<DayReport>);
+
+fn solve_with_example(day: &Day, options: &RunOptions, input_dir: &Path) -> Reports {
+    let report = solve_day(day, options, input_dir);
+    let example = match (&report.result, &options.input) {
+        (Err(AocError::Input(_)), InputSource::Default | InputSource::File(_)) => {
+            let options = RunOptions {
+                input: InputSource::Example,
+                ..options.clone()
+            };
+            Some(solve_day(day, &options, input_dir))
+        }
+        _ => None,
+    };
+    (report, example)
+}
+
+/// solves `days` one after the other on another thread, sending every report
+fn start_run(days: Vec<Day>, options: &RunOptions, input_dir: &Path, reports: &Sender<Reports>) {
+    let (options, input_dir, reports) =
+        (options.clone(), PathBuf::from(input_dir), reports.clone());
+    thread::spawn(move || {
+        for day in days {
+            if reports
+                .send(solve_with_example(&day, &options, &input_dir))
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+}
+
+/// what a key press asks for
+enum Action {
+    Run(usize),
+    RunAll,
+    Quit,
+}
+
+fn action(dashboard: &mut Dashboard, key: KeyEvent) -> Option<Action> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => {
+            dashboard.selected = dashboard.selected.saturating_sub(1)
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            dashboard.selected = (dashboard.selected + 1).min(dashboard.rows.len() - 1)
+        }
+        // days are not run again while they are still running
+        KeyCode::Enter | KeyCode::Char('r') if !dashboard.rows[dashboard.selected].running => {
+            return Some(Action::Run(dashboard.selected + 1))
+        }
+        KeyCode::Char('a') if !dashboard.running() => return Some(Action::RunAll),
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            return Some(Action::Quit)
+        }
+        KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+        _ => {}
+    }
+    None
+}
+
+fn load_answers(options: &RunOptions, input_dir: &Path) -> Option<Answers> {
+    match options.input {
+        InputSource::Default => Answers::load(&answers_path(input_dir)).ok(),
+        _ => None,
+    }
+}
+
+/// Shows the whole calendar full-screen and runs all days, then re-runs days on
+/// key presses until `q` is pressed
+pub fn dashboard(registry: &Registry, options: &RunOptions, input_dir: &Path) -> io::Result<()> {
+    let mut dashboard = Dashboard::new(registry);
+    let (sender, reports) = mpsc::channel();
+    let run = |dashboard: &mut Dashboard, days: Vec<usize>| {
+        let days: Vec<Day> = days
+            .iter()
+            .filter_map(|day| registry.get(*day))
+            .copied()
+            .collect();
+        for day in days.iter() {
+            dashboard.rows[day.number - 1].running = true;
+        }
+        start_run(days, options, input_dir, &sender);
+    };
+    run(&mut dashboard, (1..=DAYS).collect());
+    let _screen = FullScreen::enter()?;
+    loop {
+        draw(&dashboard)?;
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                match action(&mut dashboard, key) {
+                    Some(Action::Run(day)) => run(&mut dashboard, vec![day]),
+                    Some(Action::RunAll) => run(&mut dashboard, (1..=DAYS).collect()),
+                    Some(Action::Quit) => return Ok(()),
+                    None => {}
+                }
+            }
+        }
+        for (report, example) in reports.try_iter() {
+            // answers may have been submitted in the meantime
+            let answers = load_answers(options, input_dir);
+            let check = |part, answer: Option<&str>| {
+                verdict(&options.input, answers.as_ref(), report.day, part, answer)
+            };
+            let row = &mut dashboard.rows[report.day - 1];
+            row.update(&report, check);
+            if let Some(example) = example {
+                row.update_from_example(&example);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{action, solve_with_example, Action, AnswerState, Dashboard, Implementation};
+    use crate::{
+        answers::Verdict,
+        cli::RunOptions,
+        error::AocError,
+        helper::{out, InputError},
+        registry,
+        timing::Samples,
+        DayReport, PartReport,
+    };
+    use crossterm::event::{KeyCode, KeyEvent};
+    use std::{env, process};
+
+    fn report(day: usize, parts: Vec<Result<u64, &str>>) -> DayReport {
+        let parts = parts.into_iter().zip(1..);
+        let parts = parts.map(|(output, part)| PartReport {
+            part,
+            output: output
+                .map(|answer| out(part as usize).answer("answer", answer))
+                .map_err(|message| AocError::Panic {
+                    day: Some(day),
+                    message: message.into(),
+                }),
+            samples: Samples::new(vec![std::time::Duration::from_millis(2)]),
+        });
+        DayReport {
+            day,
+            result: Ok((Samples::default(), parts.collect())),
+        }
+    }
+
+    #[test]
+    fn rows() {
+        let mut dashboard = Dashboard::new(&registry());
+        assert_eq!(dashboard.rows.len(), 25);
+        assert_eq!(
+            dashboard.rows[0].implementation,
+            Implementation::Implemented
+        );
+        // stubs are only known after a run
+        assert_eq!(
+            dashboard.rows[15].implementation,
+            Implementation::Implemented
+        );
+        assert_eq!(dashboard.rows[16].implementation, Implementation::Missing);
+        assert_eq!(dashboard.rows[16].cells()[1..4], ["missing", "", ""]);
+
+        let known = |part, answer: Option<&str>| match part {
+            1 => Some(Verdict::of(Some("7"), answer)),
+            _ => None,
+        };
+        dashboard.rows[0].update(&report(1, vec![Ok(7), Ok(5)]), known);
+        let row = &dashboard.rows[0];
+        assert_eq!(row.parts, [AnswerState::Verified, AnswerState::Unverified]);
+        assert_eq!(
+            row.cells(),
+            ["1", "implemented", "verified", "unverified", "4.00ms", "★·"].map(String::from)
+        );
+        assert_eq!(dashboard.stars(), 1);
+
+        let todo = "not yet implemented at src/day2.rs:3:5";
+        dashboard.rows[1].update(&report(2, vec![Ok(1), Err(todo)]), known);
+        let row = &dashboard.rows[1];
+        assert_eq!(row.implementation, Implementation::Stubbed);
+        assert_eq!(row.parts, [AnswerState::Failing, AnswerState::Failing]);
+
+        let missing = DayReport {
+            day: 3,
+            result: Err(AocError::Input(InputError::NoExample { day: 3 })),
+        };
+        dashboard.rows[2].update(&missing, known);
+        assert_eq!(dashboard.rows[2].cells()[2], "no input");
+    }
+
+    /// day 15 has no input, but its example runs into a `todo!()`
+    #[test]
+    fn stubs_without_an_input() {
+        let registry = registry();
+        let day = registry.get(15).unwrap();
+        let input_dir = env::temp_dir().join(format!("aoc-no-inputs-{}", process::id()));
+        let options = RunOptions {
+            days: Some(15..=15),
+            ..RunOptions::default()
+        };
+        let (report, example) = solve_with_example(day, &options, &input_dir);
+        let mut dashboard = Dashboard::new(&registry);
+        let row = &mut dashboard.rows[14];
+        row.update(&report, |_, _| None);
+        assert_eq!(row.parts, [AnswerState::NoInput; 2]);
+        assert_eq!(row.implementation, Implementation::Implemented);
+        row.update_from_example(&example.unwrap());
+        assert_eq!(row.implementation, Implementation::Stubbed);
+        assert_eq!(row.cells()[1..4], ["stubbed", "no input", "no input"]);
+    }
+
+    #[test]
+    fn keys() {
+        let mut dashboard = Dashboard::new(&registry());
+        let mut press = |code| action(&mut dashboard, KeyEvent::from(code));
+        assert!(press(KeyCode::Up).is_none());
+        press(KeyCode::Down);
+        press(KeyCode::Char('j'));
+        assert!(matches!(press(KeyCode::Enter), Some(Action::Run(3))));
+        assert!(matches!(press(KeyCode::Char('a')), Some(Action::RunAll)));
+        assert!(matches!(press(KeyCode::Char('q')), Some(Action::Quit)));
+        for _ in 0..30 {
+            press(KeyCode::Down);
+        }
+        assert_eq!(dashboard.selected, 24);
+    }
+
+    #[test]
+    fn no_overlapping_runs() {
+        let mut dashboard = Dashboard::new(&registry());
+        dashboard.rows[3].running = true;
+        let press = |dashboard: &mut Dashboard, code| action(dashboard, KeyEvent::from(code));
+        assert!(press(&mut dashboard, KeyCode::Char('a')).is_none());
+        assert!(matches!(
+            press(&mut dashboard, KeyCode::Enter),
+            Some(Action::Run(1))
+        ));
+        dashboard.selected = 3;
+        assert!(press(&mut dashboard, KeyCode::Enter).is_none());
+        dashboard.rows[3].update(&report(4, vec![Ok(1), Ok(2)]), |_, _| None);
+        assert!(matches!(
+            press(&mut dashboard, KeyCode::Char('a')),
+            Some(Action::RunAll)
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::helper::{load_input_for_day, out};
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{line, lines, parse_all};
+use crate::solution::Solution;
 
 pub enum LineStatus {
     Corrupted { illegal_char: char },
@@ -57,56 +60,86 @@ pub fn parse_line(
     }
 }
 
-pub fn run() {
-    let _input = "[({(<(())[]>[[{[]{<()<>>
-[(()[<>])]({[<{<<[]>>(
-{([(<{}[<>[]}>{[]{[(<()>
-(((({<>}<{<{<>}{[]{[]{}
-[[<[([]))<([[{}[[()]]]
-[{[{({}]{}}([{[{{{}}([]
-{<[[]]>}<{[{[{[]{()[[[]
-[<(<(<(<{}))><([]([]()
-<{([([[(<>()){}]>(<<{{
-<{([{{}}[<[[[<>{}]]]>[]]"
-        .to_string();
-    let input = load_input_for_day(10);
+fn incomplete_line_score(sequence: Vec<char>, pmap: &HashMap<char, u32>) -> u64 {
+    let mut starting_score = 0;
+    for c in sequence.into_iter() {
+        starting_score *= 5;
+        starting_score += *pmap.get(&c).unwrap() as u64;
+    }
+    starting_score
+}
+
+pub struct Day10 {
+    lines: Vec<String>,
+}
+
+fn symbol_maps() -> (HashMap<char, char>, HashMap<char, char>) {
     let symbol_map = HashMap::from([('{', '}'), ('[', ']'), ('(', ')'), ('<', '>')]);
     let rev_symbol_map: HashMap<char, char> =
         HashMap::from_iter(symbol_map.iter().map(|(k, v)| (*v, *k)));
-    let point_map_corrupted: HashMap<char, u32> =
-        HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]);
-    let point_map_incomplete: HashMap<char, u32> =
-        HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)]);
+    (symbol_map, rev_symbol_map)
+}
+
+impl Day10 {
+    fn line_statuses(&self) -> impl Iterator<Item = Result<LineStatus, AocError>> + '_ {
+        let (symbol_map, rev_symbol_map) = symbol_maps();
+        self.lines.iter().map(move |line| {
+            parse_line(line, &symbol_map, &rev_symbol_map).map_err(AocError::solve)
+        })
+    }
+}
 
-    fn incomplete_line_score(sequence: Vec<char>, pmap: &HashMap<char, u32>) -> u64 {
-        let mut starting_score = 0;
-        for c in sequence.into_iter() {
-            starting_score *= 5;
-            starting_score += *pmap.get(&c).unwrap() as u64;
+impl Solution for Day10 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let (symbol_map, rev_symbol_map) = symbol_maps();
+        let lines = parse_all(input, lines(line))?;
+        for line in lines.iter() {
+            let invalid = line
+                .char_indices()
+                .find(|(_, c)| !symbol_map.contains_key(c) && !rev_symbol_map.contains_key(c));
+            if let Some((i, c)) = invalid {
+                return Err(AocError::parse(
+                    input,
+                    &line[i..],
+                    format!("Invalid Character found: '{}'", c),
+                ));
+            }
         }
-        starting_score
+        Ok(Day10 {
+            lines: lines.into_iter().map(|line| line.to_string()).collect(),
+        })
     }
-    let mut score_corrupted: u64 = 0;
-    let mut scores_incomplete: Vec<u64> = Vec::new();
-    for line in input.lines() {
-        match parse_line(line, &symbol_map, &rev_symbol_map).unwrap() {
-            LineStatus::Corrupted { illegal_char } => {
-                // dbg!(illegal_char);
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let point_map_corrupted: HashMap<char, u32> =
+            HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]);
+        let mut score_corrupted: u64 = 0;
+        for status in self.line_statuses() {
+            if let LineStatus::Corrupted { illegal_char } = status? {
                 let char_score = point_map_corrupted.get(&illegal_char).unwrap();
                 score_corrupted += *char_score as u64;
             }
-            LineStatus::Complete => {}
-            LineStatus::Incomplete {
+        }
+        Ok(out(1).answer("corrupted score", score_corrupted))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let point_map_incomplete: HashMap<char, u32> =
+            HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)]);
+        let mut scores_incomplete: Vec<u64> = Vec::new();
+        for status in self.line_statuses() {
+            if let LineStatus::Incomplete {
                 completion_sequence,
-            } => {
+            } = status?
+            {
                 let line_score = incomplete_line_score(completion_sequence, &point_map_incomplete);
-                // println!("{}: {}", line, line_score);
                 scores_incomplete.push(line_score);
             }
         }
+        scores_incomplete.sort_unstable();
+        let score_incomplete = *scores_incomplete
+            .get(scores_incomplete.len() / 2)
+            .ok_or_else(|| AocError::solve("There are no incomplete lines!"))?;
+        Ok(out(2).answer("incomplete score", score_incomplete))
     }
-    scores_incomplete.sort_unstable();
-    let score_incomplete = scores_incomplete[scores_incomplete.len() / 2];
-    out(1).var("corrupted score", score_corrupted).print();
-    out(2).var("incomplete score", score_incomplete).print();
 }
@@ -1,32 +1,32 @@
-use std::{
-    error::Error,
-    fmt::Display,
-};
+use std::fmt::Display;
+use std::iter;
 
-use crate::helper::{load_input_for_day, out};
+use crate::error::AocError;
+use crate::grid::{Grid, Pos};
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{char_grid, parse_all};
+use crate::solution::Solution;
+use crate::visualize::{Cell, Frame, Highlight, Visualize};
 
-struct OctoField<const SX: usize, const SY: usize> {
-    field: [[u8; SY]; SX],
+/// the visualization stops here if the octopuses never flash all at once
+const MAX_VISUALIZED_STEPS: u64 = 10_000;
+
+#[derive(Clone)]
+struct OctoField {
+    field: Grid<u8>,
     counter: u64,
     flash_count: u64,
 }
 
-impl<const SX: usize, const SY: usize> OctoField<SX, SY> {
-    pub fn from_str(input: &str) -> Result<OctoField<SX, SY>, Box<dyn Error>> {
-        let mut field = [[0u8; SY]; SX];
-        for (y, line) in input.lines().enumerate() {
-            for (x, character) in line.trim().chars().enumerate() {
-                let field_value = character
-                    .to_digit(10)
-                    .ok_or("Invalid character in input")?
-                    .try_into()?;
-                *field
-                    .get_mut(x)
-                    .ok_or("Invalid Input size")?
-                    .get_mut(y)
-                    .ok_or("Invalid Input size")? = field_value;
-            }
-        }
+impl OctoField {
+    pub fn from_str(input: &str) -> Result<OctoField, AocError> {
+        let field = parse_all(
+            input,
+            char_grid(
+                |c| c.to_digit(10).map(|digit| digit as u8),
+                "Invalid character in input",
+            ),
+        )?;
         Ok(OctoField {
             field,
             counter: 0,
@@ -42,137 +42,146 @@ impl<const SX: usize, const SY: usize> OctoField<SX, SY> {
         //
         // Adjacent flashes can cause an octopus to flash on a step even if it begins that step with very little energy. Consider the middle octopus with 1 energy in this situation:
 
-        let mut positions_to_flash: Vec<(usize, usize)> = Vec::new();
-        let mut has_flashed = [[false; SY]; SX];
+        let mut positions_to_flash: Vec<Pos> = Vec::new();
+        let mut has_flashed = Grid::new(self.field.width(), self.field.height(), false);
         self.counter += 1;
         // detect initial flashes
-        #[allow(clippy::needless_range_loop)]
-        for x in 0..SX {
-            for y in 0..SY {
-                // we know that this is in bounds because we literally use the array size
-                // to create the iterator over x and y
-                let octopus = &mut self.field[x][y];
-                *octopus += 1;
-                if *octopus > 9 {
-                    has_flashed[x][y] = true;
-                    positions_to_flash.push((x, y));
-                }
+        for (pos, octopus) in self.field.iter_mut() {
+            *octopus += 1;
+            if *octopus > 9 {
+                has_flashed[pos] = true;
+                positions_to_flash.push(pos);
             }
         }
         // continue flashing until the flashing stops
-        while let Some((x, y)) = positions_to_flash.pop() {
-            for ((adj_x, adj_y), adj_value) in self.values_around_pos(x, y) {
-                if !has_flashed[adj_x][adj_y] {
+        while let Some(pos) = positions_to_flash.pop() {
+            for (adj_pos, adj_value) in self.field.neighbors8_mut(pos) {
+                if !has_flashed[adj_pos] {
                     *adj_value += 1;
                     if *adj_value > 9 {
-                        has_flashed[adj_x][adj_y] = true;
-                        positions_to_flash.push((adj_x, adj_y));
+                        has_flashed[adj_pos] = true;
+                        positions_to_flash.push(adj_pos);
                     }
                 }
             }
         }
         let mut flash_count_this_step = 0;
-        #[allow(clippy::needless_range_loop)]
-        for x in 0..SX {
-            for y in 0..SY {
-                if has_flashed[x][y] {
-                    flash_count_this_step += 1;
-                    self.field[x][y] = 0;
-                }
+        for (pos, octopus) in self.field.iter_mut() {
+            if has_flashed[pos] {
+                flash_count_this_step += 1;
+                *octopus = 0;
             }
         }
         self.flash_count += flash_count_this_step;
         // did all the values flash?
-        flash_count_this_step >= (SX * SY).try_into().unwrap()
+        flash_count_this_step >= (self.field.width() * self.field.height()) as u64
     }
-
-    // returns an iterator over adjacent values
-    fn values_around_pos(
-        &mut self,
-        x: usize,
-        y: usize,
-    ) -> impl Iterator<Item = ((usize, usize), &mut u8)> {
-        // Vec<&mut u8> {
-        // let mut refs = Vec::new();
-        let deltas = (-1isize..=1)
-            .flat_map(move |x| (-1isize..=1).map(move |y| (x, y)))
-            .filter(|(x, y)| !(*x == 0 && x == y));
-        let positions = deltas.filter_map(move |(dx, dy)| {
-            let (newx, newy) = (x as isize + dx, y as isize + dy);
-            // lower_bounds
-            if newx < 0 || newy < 0 {
-                return None;
-            }
-            // upper bounds
-            if newx as usize >= SX || newy as usize >= SY {
-                return None;
+    /// the energy levels, highlighting the octopuses that flashed in the last step
+    fn frame(&self) -> Frame {
+        let mut flashes = 0;
+        let cells = self.field.map(|&energy| {
+            let mut cell = Cell::new((b'0' + energy) as char);
+            if energy == 0 && self.counter > 0 {
+                cell.highlight = Highlight::Focus;
+                flashes += 1;
             }
-            Some((newx as usize, newy as usize))
+            cell
         });
-        // unsafe needed / more convenient because I know that there will never be two (x, y)
-        // that are the same and that (x, y) is in bounds. (look at how the iterator is generated
-        // above). Without using unsafe it would be very hard to get mutable references to single elements
-        positions.map(|(x, y)| unsafe {
-            let column: &mut [u8; SY] = &mut *(self.field.get_unchecked_mut(x) as *mut _);
-            ((x, y), column.get_unchecked_mut(y))
-        })
+        let caption = format!(
+            "step {}, {} flashes ({} in this step)",
+            self.counter, self.flash_count, flashes
+        );
+        Frame::new(caption, cells)
     }
 }
 
-impl<const SX: usize, const SY: usize> Display for OctoField<SX, SY> {
+impl Display for OctoField {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", "-".repeat(SX))?;
-        for y in 0..SY {
-            for x in 0..SX {
-                let value = unsafe { self.field.get_unchecked(x).get_unchecked(y) };
-                write!(f, "{}", value)?;
-            }
-            writeln!(f)?;
-        }
-        writeln!(f, "{}", "-".repeat(SX))?;
+        let width = self.field.width();
+        writeln!(f, "{}", "-".repeat(width))?;
+        write!(f, "{}", self.field)?;
+        writeln!(f, "{}", "-".repeat(width))?;
         writeln!(f, "STEPS: {}", self.counter)?;
         writeln!(f, "FLASHES: {}", self.flash_count)?;
-        writeln!(f, "{}", "-".repeat(SX))?;
+        writeln!(f, "{}", "-".repeat(width))?;
         Ok(())
     }
 }
 
-pub fn run() {
-    /*
-    let input = "11111
-    19991
-    19191
-    19991
-    11111";
+pub struct Day11 {
+    octofield: OctoField,
+}
 
-    let input = "5483143223
-        2745854711
-        5264556173
-        6141336146
-        6357385478
-        4167524645
-        2176841721
-        6882881134
-        4846848554
-        5283751526";
-    */
-    let input = &load_input_for_day(11);
-    let mut octofield = OctoField::<10, 10>::from_str(input).unwrap();
-    // println!("{}", octofield);
-    for _ in 0..100 {
-        octofield.perform_step();
-        // println!("{}", octofield);
+impl Visualize for Day11 {
+    /// every step until all octopuses flash at once
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut octofield = self.octofield.clone();
+        let first = octofield.frame();
+        let mut synchronized = false;
+        let steps = iter::from_fn(move || {
+            if synchronized || octofield.counter >= MAX_VISUALIZED_STEPS {
+                return None;
+            }
+            synchronized = octofield.perform_step();
+            Some(octofield.frame())
+        });
+        Box::new(iter::once(first).chain(steps))
     }
-    out(1).var("flashes", octofield.flash_count).print();
-    let mut octofield = OctoField::<10, 10>::from_str(input).unwrap();
-    let mut first_time_full_flash: Option<u64> = None;
-    loop {
-        if octofield.perform_step() && first_time_full_flash.is_none() {
-            first_time_full_flash = Some(octofield.counter);
-            break;
+}
+
+impl Solution for Day11 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day11 {
+            octofield: OctoField::from_str(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let mut octofield = self.octofield.clone();
+        for _ in 0..100 {
+            octofield.perform_step();
         }
+        Ok(out(1).answer("flashes", octofield.flash_count))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let mut octofield = self.octofield.clone();
+        let mut first_time_full_flash: Option<u64> = None;
+        loop {
+            if octofield.perform_step() && first_time_full_flash.is_none() {
+                first_time_full_flash = Some(octofield.counter);
+                break;
+            }
+        }
+        Ok(out(2).answer("first full flash", first_time_full_flash))
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day11;
+    use crate::{
+        fixtures::fixture,
+        helper::normalize,
+        solution::Solution,
+        visualize::{Highlight, Visualize},
+    };
+
+    #[test]
+    fn frames_until_all_flash() {
+        let day = Day11::parse(&normalize(fixture(11).unwrap().input)).unwrap();
+        let frames: Vec<_> = day.frames().collect();
+        // the initial state and the 195 steps until the first full flash
+        assert_eq!(frames.len(), 196);
+        assert_eq!(frames[0].highlighted(Highlight::Focus).count(), 0);
+        assert_eq!(
+            frames[100].caption,
+            "step 100, 1656 flashes (13 in this step)"
+        );
+        assert_eq!(frames[195].highlighted(Highlight::Focus).count(), 100);
     }
-    out(2)
-        .var("first full flash", first_time_full_flash)
-        .print();
 }
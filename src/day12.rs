@@ -1,20 +1,26 @@
-use std::{
-    collections::{HashMap, HashSet},
-    error::Error,
-    fmt::Display,
+use std::collections::{HashMap, HashSet};
+
+use nom::{
+    character::complete::{alphanumeric1, char},
+    combinator::consumed,
+    error::context,
+    sequence::separated_pair,
 };
 
-use crate::helper::{load_input_for_day, out};
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{lines, parse_all};
+use crate::solution::Solution;
 
 #[derive(Debug)]
-struct Payload<'a, T> {
+struct Payload<T> {
     value: T,
     conns: Vec<NodeID>,
     #[allow(dead_code)]
-    name: &'a str,
+    name: String,
 }
-struct Network<'a, T> {
-    nodes: HashMap<NodeID, Payload<'a, T>>,
+struct Network<T> {
+    nodes: HashMap<NodeID, Payload<T>>,
     start: Option<NodeID>,
     end: Option<NodeID>,
     id_counter: usize,
@@ -23,20 +29,8 @@ struct Network<'a, T> {
 #[derive(Hash, Clone, Debug, PartialEq, Eq, Copy)]
 pub struct NodeID(usize);
 
-#[derive(Debug)]
-struct ConnectionError {
-    msg: String,
-}
-impl Display for ConnectionError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.msg)
-    }
-}
-
-impl Error for ConnectionError {}
-
-impl<'a, T> Network<'a, T> {
-    pub fn add_node(&mut self, name: &'a str, node: T) -> NodeID {
+impl<T> Network<T> {
+    pub fn add_node(&mut self, name: &str, node: T) -> NodeID {
         let id = NodeID(self.id_counter);
         if name == "start" {
             self.start = Some(id);
@@ -46,17 +40,15 @@ impl<'a, T> Network<'a, T> {
         let payload = Payload {
             value: node,
             conns: Vec::new(),
-            name,
+            name: name.to_string(),
         };
         self.nodes.insert(id, payload);
         self.id_counter += 1;
         id
     }
-    pub fn connect(&mut self, id1: NodeID, id2: NodeID) -> Result<(), Box<dyn Error>> {
+    pub fn connect(&mut self, id1: NodeID, id2: NodeID) -> Result<(), &'static str> {
         if id1 == id2 {
-            return Err(Box::new(ConnectionError {
-                msg: "Cannot connect node with itself".into(),
-            }));
+            return Err("Cannot connect node with itself");
         }
         let n1 = self.nodes.get_mut(&id1).ok_or("Wrong ID")?;
         n1.conns.push(id2);
@@ -83,12 +75,12 @@ struct Node {
 }
 
 struct NetworkIter<'a> {
-    network: &'a Network<'a, Node>,
+    network: &'a Network<Node>,
     path_stack: Vec<(Vec<NodeID>, bool)>,
     double_caves: bool,
 }
 impl<'a> NetworkIter<'a> {
-    pub fn new(n: &'a Network<'a, Node>, double_caves: bool) -> Self {
+    pub fn new(n: &'a Network<Node>, double_caves: bool) -> Self {
         let s = match n.start {
             Some(id) => vec![(vec![id], false)],
             None => vec![],
@@ -101,7 +93,7 @@ impl<'a> NetworkIter<'a> {
     }
 }
 
-impl<'a> Iterator for NetworkIter<'a> {
+impl Iterator for NetworkIter<'_> {
     type Item = Vec<NodeID>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -147,7 +139,7 @@ impl<'a> Iterator for NetworkIter<'a> {
 }
 
 #[allow(dead_code)]
-fn print_path<'a>(p: &[(NodeID, &'a Payload<'a, Node>)]) {
+fn print_path(p: &[(NodeID, &Payload<Node>)]) {
     for (_, payload) in p.iter().rev().skip(1).rev() {
         print!("{}", payload.name);
         print!("->");
@@ -158,44 +150,57 @@ fn print_path<'a>(p: &[(NodeID, &'a Payload<'a, Node>)]) {
     println!();
 }
 
-pub fn run() {
-    #[allow(unused)]
-    let input = "start-A
-start-b
-A-c
-A-b
-b-d
-A-end
-b-end";
-    let input = load_input_for_day(12);
-    let mut network = Network::<Node>::new();
-    let node_names_duplicates = input.lines().flat_map(|line| line.split('-'));
-    let mut node_names: HashSet<&str> = HashSet::new();
-    for name in node_names_duplicates {
-        node_names.insert(name);
-    }
-    let mut node_ids = HashMap::new();
-    for name in node_names {
-        let is_lower = name.chars().next().unwrap().is_lowercase();
-        let id = network.add_node(name, Node { small: is_lower });
-        node_ids.insert(name, id);
-    }
-    for line in input.lines() {
-        let (start, end) = line.split_once('-').unwrap();
-        let idstart = node_ids.get(start).unwrap();
-        let idend = node_ids.get(end).unwrap();
-        network.connect(*idstart, *idend).unwrap();
+pub struct Day12 {
+    network: Network<Node>,
+}
+
+impl Solution for Day12 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let mut network = Network::<Node>::new();
+        let cave = || context("Missing cave name", alphanumeric1);
+        let connection = separated_pair(
+            cave(),
+            context("Expected a connection 'a-b'", char('-')),
+            cave(),
+        );
+        let connections = parse_all(input, lines(consumed(connection)))?;
+        let mut node_names: HashSet<&str> = HashSet::new();
+        for (_, (start, end)) in connections.iter() {
+            node_names.insert(start);
+            node_names.insert(end);
+        }
+        let mut node_ids = HashMap::new();
+        for name in node_names {
+            let is_lower = name.starts_with(char::is_lowercase);
+            let id = network.add_node(name, Node { small: is_lower });
+            node_ids.insert(name, id);
+        }
+        for (line, (start, end)) in connections {
+            network
+                .connect(node_ids[start], node_ids[end])
+                .map_err(|msg| AocError::parse(input, line, msg))?;
+        }
+        if network.start.is_none() || network.end.is_none() {
+            return Err(AocError::invalid("The start or end cave is missing!"));
+        }
+        Ok(Day12 { network })
     }
-    let mut count_first_task: u64 = 0;
-    for _p in NetworkIter::new(&network, false) {
-        // print_path(&p);
-        count_first_task += 1;
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let mut count_first_task: u64 = 0;
+        for _p in NetworkIter::new(&self.network, false) {
+            // print_path(&p);
+            count_first_task += 1;
+        }
+        Ok(out(1).answer("number of paths", count_first_task))
     }
-    let mut count_second_task: u64 = 0;
-    for _p in NetworkIter::new(&network, true) {
-        // print_path(&p);
-        count_second_task += 1;
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let mut count_second_task: u64 = 0;
+        for _p in NetworkIter::new(&self.network, true) {
+            // print_path(&p);
+            count_second_task += 1;
+        }
+        Ok(out(2).answer("number of paths", count_second_task))
     }
-    out(1).var("number of paths", count_first_task).print();
-    out(2).var("number of paths", count_second_task).print();
 }
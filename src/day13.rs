@@ -1,6 +1,21 @@
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
+use std::iter;
 
-use crate::helper::{load_input_for_day, out};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::value,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
+
+use crate::error::AocError;
+use crate::grid::Grid;
+use crate::helper::{out, Answer, OutputFormatter};
+use crate::parse::{lines, number, parse_all, two_sections};
+use crate::solution::Solution;
+use crate::visualize::{Cell, Frame, Highlight, Visualize};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 struct Dot {
@@ -8,7 +23,7 @@ struct Dot {
     y: i32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct TransparentPaper {
     dots: Vec<Dot>,
     width: usize,
@@ -91,72 +106,181 @@ impl TransparentPaper {
             *coord_to_update -= offset;
         }
     }
+    /// the paper as in `Display`, with the fold line highlighted if given
+    fn frame(&self, caption: String, fold_line: Option<(FoldAxis, u32)>) -> Frame {
+        let mut cells = Grid::new(self.width, self.height, Cell::new('.'));
+        for Dot { x, y } in &self.dots {
+            cells[(*x as usize, *y as usize)].symbol = '#';
+        }
+        if let Some((axis, fold_pos)) = fold_line {
+            let fold_pos = fold_pos as usize;
+            for (pos, cell) in cells.iter_mut() {
+                let (on_line, symbol) = match axis {
+                    FoldAxis::X => (pos.0 == fold_pos, '|'),
+                    FoldAxis::Y => (pos.1 == fold_pos, '-'),
+                };
+                if on_line {
+                    *cell = Cell {
+                        symbol,
+                        highlight: Highlight::Focus,
+                    };
+                }
+            }
+        }
+        Frame::new(caption, cells)
+    }
 }
 
 impl Display for TransparentPaper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut document = vec![vec!['.'; self.width as usize]; self.height as usize];
+        let mut document = Grid::new(self.width, self.height, '.');
         for Dot { x, y } in &self.dots {
-            document[*y as usize][*x as usize] = '#';
-        }
-        for line in document {
-            writeln!(f, "{}", line.into_iter().collect::<String>())?;
+            document[(*x as usize, *y as usize)] = '#';
         }
-        Ok(())
+        write!(f, "{}", document)
     }
 }
 
-pub fn run() {
-    #[allow(unused)]
-    let input = "6,10
-0,14
-9,10
-0,3
-10,4
-4,11
-6,0
-6,12
-4,1
-0,13
-10,12
-3,4
-3,0
-8,4
-1,10
-2,14
-8,10
-9,0
-
-fold along y=7
-fold along x=5";
-    let input = load_input_for_day(13);
-    let mut line_iter = input.lines();
-    let dots_str = line_iter
-        .by_ref()
-        .take_while(|line| !line.starts_with('\n') && !line.is_empty());
-
-    let mut paper = TransparentPaper::new();
-    for dot in dots_str {
-        let (x, y) = dot.split_once(',').unwrap();
-        let (x, y): (i32, i32) = (x.parse().unwrap(), y.parse().unwrap());
-        paper.add_dot(x, y);
+pub struct Day13 {
+    paper: TransparentPaper,
+    folds: Vec<(FoldAxis, u32)>,
+}
+
+/// folds the paper, as long as no dot lies on the fold line
+fn fold(paper: &mut TransparentPaper, axis: FoldAxis, fold_pos: u32) -> Result<(), AocError> {
+    let on_fold_line = paper.dots.iter().any(|dot| match axis {
+        FoldAxis::X => dot.x == fold_pos as i32,
+        FoldAxis::Y => dot.y == fold_pos as i32,
+    });
+    if on_fold_line {
+        return Err(AocError::solve(format!(
+            "There is a dot on the fold line {:?}={}",
+            axis, fold_pos
+        )));
     }
-    let mut first_fold_count = 0;
-    for (i, instruction) in line_iter.enumerate() {
-        let instruction = &instruction[11..];
-        let (axis_str, fold_pos) = instruction.split_once('=').unwrap();
-        let axis = match axis_str {
-            "x" => FoldAxis::X,
-            "y" => FoldAxis::Y,
-            _ => panic!(),
-        };
-        let fold_pos = fold_pos.parse::<u32>().unwrap();
-        paper.fold(fold_pos, axis);
-        if i == 0 {
-            first_fold_count = paper.get_num_dots();
+    paper.fold(fold_pos, axis);
+    Ok(())
+}
+
+impl Visualize for Day13 {
+    /// the paper before and after every fold
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut paper = self.paper.clone();
+        let first = paper.frame(format!("{} dots", paper.get_num_dots()), None);
+        let mut folds = self.folds.iter().enumerate();
+        // the fold whose line is shown, but which has not been done yet
+        let mut pending = None;
+        let mut failed = false;
+        let steps = iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            match pending.take() {
+                None => {
+                    let (i, &(axis, fold_pos)) = folds.next()?;
+                    pending = Some((i, axis, fold_pos));
+                    let caption = format!("fold {} along {:?}={}", i + 1, axis, fold_pos);
+                    Some(paper.frame(caption, Some((axis, fold_pos))))
+                }
+                Some((i, axis, fold_pos)) => {
+                    let caption = match fold(&mut paper, axis, fold_pos) {
+                        Ok(()) => format!("after fold {}, {} dots", i + 1, paper.get_num_dots()),
+                        Err(err) => {
+                            failed = true;
+                            err.to_string()
+                        }
+                    };
+                    Some(paper.frame(caption, None))
+                }
+            }
+        });
+        Box::new(iter::once(first).chain(steps))
+    }
+}
+
+impl Solution for Day13 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let dot = separated_pair(
+            number::<u16>,
+            context("Expected a dot 'x,y'", char(',')),
+            number::<u16>,
+        );
+        let axis = alt((value(FoldAxis::X, char('x')), value(FoldAxis::Y, char('y'))));
+        let fold = preceded(
+            context("Expected 'fold along <axis>=<pos>'", tag("fold along ")),
+            separated_pair(context("Unknown fold axis", axis), char('='), number::<u16>),
+        );
+        let (dots, folds) = parse_all(input, two_sections(lines(dot), lines(fold)))?;
+        let mut paper = TransparentPaper::new();
+        for (x, y) in dots {
+            paper.add_dot(x as i32, y as i32);
+        }
+        let folds = folds
+            .into_iter()
+            .map(|(axis, fold_pos)| (axis, fold_pos as u32))
+            .collect();
+        Ok(Day13 { paper, folds })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let mut paper = self.paper.clone();
+        if let Some(&(axis, fold_pos)) = self.folds.first() {
+            fold(&mut paper, axis, fold_pos)?;
+        }
+        Ok(out(1).answer("number of dots", paper.get_num_dots()))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let mut paper = self.paper.clone();
+        for &(axis, fold_pos) in self.folds.iter() {
+            fold(&mut paper, axis, fold_pos)?;
+        }
+        Ok(out(2).answer("ASCII-Art output code", Answer::grid(&paper.to_string())))
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day13;
+    use crate::error::AocError;
+    use crate::fixtures::fixture;
+    use crate::helper::normalize;
+    use crate::solution::Solution;
+    use crate::visualize::{Highlight, Visualize};
+
+    #[test]
+    fn unknown_fold_axis() {
+        let input = "6,10\n0,14\n\nfold along y=7\nfold along z=5";
+        match Day13::parse(input) {
+            Err(AocError::Parse {
+                location: Some(location),
+                ..
+            }) => {
+                assert_eq!((location.line, location.column), (5, 12));
+            }
+            _ => panic!("Expected a located parse error"),
         }
     }
-    out(1).var("number of dots", first_fold_count).print();
-    out(2).var("ASCII-Art output code", "").print();
-    print!("{}", paper);
+
+    #[test]
+    fn frames_show_every_fold() {
+        let day = Day13::parse(&normalize(fixture(13).unwrap().input)).unwrap();
+        let frames: Vec<_> = day.frames().collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1].caption, "fold 1 along Y=7");
+        assert_eq!(frames[1].highlighted(Highlight::Focus).count(), 11);
+        assert_eq!(frames[2].caption, "after fold 1, 17 dots");
+        let last = frames[4].to_string();
+        assert_eq!(
+            last,
+            format!(
+                "after fold 2, 16 dots\n{}",
+                fixture(13).unwrap().expected(2).unwrap()
+            )
+        );
+    }
 }
@@ -1,8 +1,18 @@
 use std::collections::HashMap;
 
-use crate::helper::{load_input_for_day, out};
+use nom::{
+    character::complete::{alpha1, satisfy},
+    combinator::not,
+    error::context,
+    sequence::{terminated, tuple},
+};
 
-#[derive(Debug)]
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{arrow, lines, parse_all, two_sections};
+use crate::solution::Solution;
+
+#[derive(Debug, Clone)]
 struct Polymer {
     rules: HashMap<[char; 2], ([char; 2], [char; 2])>,
     polymer: HashMap<[char; 2], u64>,
@@ -10,39 +20,42 @@ struct Polymer {
 }
 
 impl Polymer {
-    pub fn from_input<'a>(mut lines: impl Iterator<Item = &'a str>) -> Self {
-        let mut rules = HashMap::new();
-        let polymer_line = lines.next().unwrap().as_bytes();
+    pub fn from_input(input: &str) -> Result<Self, AocError> {
+        let element = || satisfy(char::is_alphabetic);
+        let pair = context("Expected a pair of elements", tuple((element(), element())));
+        let insert = context(
+            "Expected a single element",
+            terminated(element(), not(element())),
+        );
+        let rule = arrow(pair, insert);
+        let (polymer_line, rule_list) = parse_all(input, two_sections(alpha1, lines(rule)))?;
+        let polymer_line: Vec<char> = polymer_line.chars().collect();
         let mut polymer = HashMap::new();
         for window in polymer_line.windows(2) {
-            let window = [window[0] as char, window[1] as char];
+            let window = [window[0], window[1]];
             *polymer.entry(window).or_insert(0) += 1;
         }
-        lines.next();
-        for rule in lines {
-            let (pair, insert) = rule.trim().split_once(" -> ").unwrap();
-            let pair = pair.as_bytes();
-            let [e1, e2] = [pair[0] as char, pair[1] as char];
-            let insert = insert.as_bytes()[0] as char;
+        let mut rules = HashMap::new();
+        for ((e1, e2), insert) in rule_list {
             rules.insert([e1, e2], ([e1, insert], [insert, e2]));
         }
-        let rightmost = match !polymer_line.is_empty() {
-            true => Some(*polymer_line.last().unwrap() as char),
-            false => None,
-        };
 
-        Self {
+        Ok(Self {
             rules,
             polymer,
-            rightmost_element: rightmost,
-        }
+            rightmost_element: polymer_line.last().copied(),
+        })
     }
     pub fn polymerization_step(&mut self) {
         let mut added_elements = HashMap::new();
-        for (pair, (to_increase1, to_increase2)) in &self.rules {
-            if let Some(count) = self.polymer.get(pair) {
-                *added_elements.entry(*to_increase1).or_insert(0) += count;
-                *added_elements.entry(*to_increase2).or_insert(0) += count;
+        for (pair, count) in &self.polymer {
+            match self.rules.get(pair) {
+                Some((to_increase1, to_increase2)) => {
+                    *added_elements.entry(*to_increase1).or_insert(0) += count;
+                    *added_elements.entry(*to_increase2).or_insert(0) += count;
+                }
+                // pairs without a rule stay as they are
+                None => *added_elements.entry(*pair).or_insert(0) += count,
             }
         }
         self.polymer = added_elements;
@@ -59,49 +72,46 @@ impl Polymer {
     }
 }
 
-pub fn run() {
-    let _input = "NNCB
+pub struct Day14 {
+    polymer: Polymer,
+}
 
-CH -> B
-HH -> N
-CB -> H
-NH -> C
-HB -> C
-HC -> B
-HN -> C
-NN -> C
-BH -> H
-NC -> B
-NB -> B
-BN -> B
-BB -> N
-BC -> B
-CC -> N
-CN -> C";
-    let input = load_input_for_day(14);
-    let mut polymer = Polymer::from_input(input.lines());
-    for _ in 0..10 {
-        polymer.polymerization_step();
+impl Day14 {
+    /// difference between the most and the least common element
+    /// after the given number of steps
+    fn spread_after(&self, steps: usize) -> Result<u64, AocError> {
+        let mut polymer = self.polymer.clone();
+        for _ in 0..steps {
+            polymer.polymerization_step();
+        }
+        let counts: Vec<(char, u64)> = polymer.count_elements().into_iter().collect();
+        let most_common = counts.iter().max_by_key(|(_c, count)| count);
+        let least_common = counts.iter().min_by_key(|(_c, count)| count);
+        match (most_common, least_common) {
+            (Some(most_common), Some(least_common)) => Ok(most_common.1 - least_common.1),
+            _ => Err(AocError::solve("The polymer template is empty!")),
+        }
     }
-    let counts: Vec<(char, u64)> = polymer.count_elements().into_iter().collect();
-    let most_common_after10 = counts.iter().max_by_key(|(_c, count)| count).unwrap();
-    let least_common_after10 = counts.iter().min_by_key(|(_c, count)| count).unwrap();
-    for _ in 0..30 {
-        polymer.polymerization_step();
+}
+
+impl Solution for Day14 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day14 {
+            polymer: Polymer::from_input(input)?,
+        })
     }
-    let counts: Vec<(char, u64)> = polymer.count_elements().into_iter().collect();
-    let most_common_after40 = counts.iter().max_by_key(|(_c, count)| count).unwrap();
-    let least_common_after40 = counts.iter().min_by_key(|(_c, count)| count).unwrap();
-    out(1)
-        .var(
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        Ok(out(1).answer(
             "most common - least common after 10 steps",
-            most_common_after10.1 - least_common_after10.1,
-        )
-        .print();
-    out(3)
-        .var(
+            self.spread_after(10)?,
+        ))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        Ok(out(2).answer(
             "most common - least common after 40 steps",
-            most_common_after40.1 - least_common_after40.1,
-        )
-        .print();
+            self.spread_after(40)?,
+        ))
+    }
 }
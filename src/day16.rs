@@ -1,12 +1,11 @@
-use std::fmt::{Binary, Debug};
-use std::ops::{Add, AddAssign, Shl, ShlAssign};
+use std::fmt::Debug;
+use std::ops::{AddAssign, ShlAssign};
 
-use crate::helper::load_input_for_day;
+use crate::error::AocError;
+use crate::helper::OutputFormatter;
+use crate::solution::Solution;
 
-#[derive(Debug)]
-struct IsNotHexError;
-
-fn hex_to_bin(c: char) -> Result<[bool; 4], IsNotHexError> {
+fn hex_to_bin(c: char) -> Option<[bool; 4]> {
     let [a, b, c, d] = match c {
         '0' => [0u8, 0, 0, 0],
         '1' => [0, 0, 0, 1],
@@ -24,11 +23,12 @@ fn hex_to_bin(c: char) -> Result<[bool; 4], IsNotHexError> {
         'D' => [1, 1, 0, 1],
         'E' => [1, 1, 1, 0],
         'F' => [1, 1, 1, 1],
-        _ => Err(IsNotHexError)?,
+        _ => return None,
     };
-    Ok([a != 0, b != 0, c != 0, d != 0])
+    Some([a != 0, b != 0, c != 0, d != 0])
 }
 
+#[allow(dead_code)]
 enum Packet {
     LiteralValue {
         version: u8,
@@ -36,11 +36,11 @@ enum Packet {
     },
     Operator {
         version: u8,
-        packets: Vec<Box<Packet>>,
+        packets: Vec<Packet>,
         length: LengthType,
     },
     TopLevel {
-        packets: Vec<Box<Packet>>,
+        packets: Vec<Packet>,
     },
 }
 
@@ -64,25 +64,17 @@ where
     num
 }
 
-#[derive(Debug)]
-enum PacketError {
-    Invalid,
-    EmptyInput,
-}
-
 /// parses the bits for a packet.
 ///
 /// if the packet contains other packets, the bits belonging to
 /// the remaining packets will be returned as well
-fn parse_packet(bit_iter: &mut impl Iterator<Item = bool>) -> Result<Packet, PacketError> {
+fn parse_packet(bit_iter: &mut impl Iterator<Item = bool>) -> Result<Packet, AocError> {
     let first_three_bits: Vec<bool> = bit_iter.take(3).collect();
     if first_three_bits.len() != 3 {
-        return Err(PacketError::EmptyInput);
+        return Err(AocError::invalid("The transmission ended unexpectedly"));
     }
     let version: u8 = bits_to_num(first_three_bits.into_iter());
-    dbg!(version);
     let type_id: u8 = bits_to_num(bit_iter.take(3));
-    dbg!(type_id);
     #[allow(clippy::single_match)]
     match type_id {
         4 => {
@@ -91,7 +83,6 @@ fn parse_packet(bit_iter: &mut impl Iterator<Item = bool>) -> Result<Packet, Pac
             let mut last_bit = false;
             while !last_bit {
                 last_bit = !bit_iter.next().unwrap_or(false);
-                println!("TEst");
                 // not the last 5 bit segment
                 literal_bits.extend(bit_iter.take(4))
             }
@@ -102,7 +93,9 @@ fn parse_packet(bit_iter: &mut impl Iterator<Item = bool>) -> Result<Packet, Pac
         }
         _ => {
             // Operator
-            let length_type_id: bool = bit_iter.next().ok_or(PacketError::Invalid)?;
+            let length_type_id: bool = bit_iter
+                .next()
+                .ok_or_else(|| AocError::invalid("The packet has no length type id"))?;
             let length = match length_type_id {
                 false => {
                     // the next 15 bits are the total
@@ -128,6 +121,7 @@ fn parse_packet(bit_iter: &mut impl Iterator<Item = bool>) -> Result<Packet, Pac
 
 fn parse_protocol(mut stream: impl Iterator<Item = bool>) -> Vec<Packet> {
     // packet / subpacket-count pairs
+    #[allow(clippy::useless_vec)]
     let mut packet_stack = vec![(
         Packet::TopLevel {
             packets: Vec::new(),
@@ -151,7 +145,7 @@ fn parse_protocol(mut stream: impl Iterator<Item = bool>) -> Vec<Packet> {
             true => {
                 // traverse the stack in a reverse manner and collect
                 // all children until an operator is reached
-                let mut parent = packet_stack.last_mut().unwrap();
+                let _parent = packet_stack.last_mut().unwrap();
 
                 // let mut collected_children = Vec::new();
                 // let (last_packet, count): &mut (Packet, u32) = match packet_stack.last_mut() {
@@ -174,19 +168,38 @@ fn parse_protocol(mut stream: impl Iterator<Item = bool>) -> Vec<Packet> {
     todo!()
 }
 
-pub fn run() {
-    let input = load_input_for_day(16);
-    let input_bits = input.chars().flat_map(|c| hex_to_bin(c).unwrap());
+pub struct Day16 {
+    bits: Vec<bool>,
 }
 
-mod tests {
-    use crate::day16::LengthType;
+impl Solution for Day16 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let mut bits = Vec::with_capacity(4 * input.len());
+        for (i, c) in input.trim_end().char_indices() {
+            let nibble = hex_to_bin(c).ok_or_else(|| {
+                AocError::parse(input, &input[i..], "Not a hexadecimal character")
+            })?;
+            bits.extend(nibble);
+        }
+        Ok(Day16 { bits })
+    }
 
-    #[allow(unused_imports)]
-    use super::{hex_to_bin, parse_packet, Packet};
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let _packets = parse_protocol(self.bits.iter().copied());
+        // TODO: the sum of the versions of all packets
+        todo!()
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hex_to_bin, parse_packet, LengthType, Packet};
 
-    #[allow(unused)]
-    fn assert_eq_to_bitstring(bitstring: &str, bits: &Vec<bool>) {
+    fn assert_eq_to_bitstring(bitstring: &str, bits: &[bool]) {
         let mut all_equal = bitstring.len() == bits.len();
         for (i, bit_char) in bitstring.chars().enumerate() {
             if !all_equal {
@@ -1,72 +1,99 @@
-use crate::helper::{load_input_for_day, out};
+use nom::{
+    branch::alt, bytes::complete::tag, character::complete::char, combinator::value,
+    error::context, sequence::separated_pair,
+};
 
-pub fn run() {
-    // load input
-    let input = load_input_for_day(2);
-    // convert to better format
-    let instructions: Vec<(&str, usize)> = input
-        .split('\n')
-        .map(|instruction| {
-            // extract commands
-            let mut instruction = instruction.split(' ');
-            let command = instruction.next().expect("Empty instruction!");
-            let amount_str = instruction.next().expect("Missing amount to move!");
-            // convert the amount to a number
-            let amount = amount_str
-                .parse::<usize>()
-                .expect("Some inputs are not numbers!");
-            (command, amount)
-        })
-        .collect();
-    // ---------------------------------- Task 1 ----------------------------------
-    // starting position
-    let mut position = [0, 0];
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{lines, number, parse_all, PResult};
+use crate::solution::Solution;
 
-    // manipulate position
-    for (command, amount) in instructions.iter() {
-        match *command {
-            "forward" => position[0] += amount,
-            "down" => position[1] += amount,
-            "up" => position[1] -= amount,
-            _ => panic!("Invalid command!"),
+#[derive(Clone, Copy)]
+enum Command {
+    Forward,
+    Down,
+    Up,
+}
+
+/// a line like `forward 5`
+fn instruction(input: &str) -> PResult<'_, (Command, usize)> {
+    let command = alt((
+        value(Command::Forward, tag("forward")),
+        value(Command::Down, tag("down")),
+        value(Command::Up, tag("up")),
+    ));
+    separated_pair(
+        context("Invalid command!", command),
+        context("Missing amount to move!", char(' ')),
+        number,
+    )(input)
+}
+
+pub struct Day2 {
+    instructions: Vec<(Command, usize)>,
+}
+
+impl Solution for Day2 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let instructions = parse_all(input, lines(instruction))?;
+        Ok(Day2 { instructions })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        // starting position
+        let mut position = [0usize, 0];
+
+        // manipulate position
+        for &(command, amount) in self.instructions.iter() {
+            match command {
+                Command::Forward => position[0] += amount,
+                Command::Down => position[1] += amount,
+                Command::Up => position[1] = rise(position[1], amount)?,
+            }
         }
+        Ok(out(1)
+            .var("end position", position)
+            .answer("product", position[0] * position[1]))
     }
-    out(1)
-        .var("end position", position)
-        .var("product", position[0] * position[0])
-        .print();
-    // ---------------------------------- Task 2 ----------------------------------
-    // starting position [aim, horizontal_position, depth]
-    let mut depth = 0;
-    let mut aim = 0;
-    let mut hpos = 0;
 
-    // manipulate position
-    //  From the puzzle description:
-    // - down X increases your aim by X units.
-    // - up X decreases your aim by X units.
-    // - forward X does two things:
-    //    - It increases your horizontal position by X units.
-    //    - It increases your depth by your aim multiplied by X.
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        // starting position [aim, horizontal_position, depth]
+        let mut depth = 0;
+        let mut aim = 0;
+        let mut hpos = 0;
 
-    for (command, amount) in instructions.iter() {
-        match *command {
-            "forward" => {
-                // increase horizontal position
-                hpos += amount;
-                // increase depth
-                depth += aim * amount;
+        // manipulate position
+        //  From the puzzle description:
+        // - down X increases your aim by X units.
+        // - up X decreases your aim by X units.
+        // - forward X does two things:
+        //    - It increases your horizontal position by X units.
+        //    - It increases your depth by your aim multiplied by X.
+
+        for &(command, amount) in self.instructions.iter() {
+            match command {
+                Command::Forward => {
+                    // increase horizontal position
+                    hpos += amount;
+                    // increase depth
+                    depth += aim * amount;
+                }
+                Command::Down => aim += amount,
+                Command::Up => aim = rise(aim, amount)?,
             }
-            "down" => aim += amount,
-            "up" => aim -= amount,
-            _ => panic!("Invalid command!"),
         }
+
+        Ok(out(2)
+            .var("aim", aim)
+            .var("hpos", hpos)
+            .var("depth", depth)
+            .answer("product", depth * hpos))
     }
+}
 
-    out(2)
-        .var("aim", aim)
-        .var("hpos", hpos)
-        .var("depth", depth)
-        .var("product", depth * hpos)
-        .print();
+/// moving up is not possible beyond the surface
+fn rise(value: usize, amount: usize) -> Result<usize, AocError> {
+    value
+        .checked_sub(amount)
+        .ok_or_else(|| AocError::solve("The submarine would rise above the surface!"))
 }
@@ -1,4 +1,7 @@
-use crate::helper::{load_input_for_day, out};
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{char_grid, parse_all};
+use crate::solution::Solution;
 
 /// Before the conversion, the string is a file of the format
 /// ```text
@@ -11,11 +14,16 @@ use crate::helper::{load_input_for_day, out};
 /// 011001110011
 /// ```
 /// Afterwards it is a vector, containing the lines
-/// Each line stores a list of digits (as chars)
-fn parse_input(input: String) -> Vec<Vec<char>> {
-    let lines = input.split('\n');
-
-    lines.map(|l| l.chars().collect()).collect()
+/// Each line stores a list of bits
+fn parse_input(input: &str) -> Result<Vec<Vec<bool>>, AocError> {
+    let bit = |c| match c {
+        '0' => Some(false),
+        '1' => Some(true),
+        _ => None,
+    };
+    let message = "Invalid input data! Contains characters that are not eather 1 or 0.";
+    let data = parse_all(input, char_grid(bit, message))?;
+    Ok(data.rows().map(<[bool]>::to_vec).collect())
 }
 
 const BASE: usize = 2;
@@ -32,19 +40,6 @@ fn bitvec_to_usize(bitvec: &[bool]) -> usize {
     output
 }
 
-fn charvec_to_bitvec(int_string: &[char]) -> Vec<bool> {
-    let mut output = Vec::with_capacity(int_string.len());
-    for digit in int_string.iter() {
-        // check the digit
-        match digit {
-            '0' => output.push(false),
-            '1' => output.push(true),
-            _ => panic!("Invalid input data! Contains characters that are not eather 1 or 0."),
-        }
-    }
-    output
-}
-
 fn find_oxygen_generator(bitvec: &mut Vec<Vec<bool>>) {
     for column_pos in 0..bitvec[0].len() {
         // determine if 1 or 0 is more common in this column
@@ -64,6 +59,10 @@ fn find_co2_scrubber(bitvec: &mut Vec<Vec<bool>>) {
         // determine if 1 or 0 is more common in this column
         let column = bitvec.iter().filter(|row| row[column_pos]);
         let column_sum = column.count();
+        // if all rows agree, the other bit does not occur and nothing is removed
+        if column_sum == 0 || column_sum == bitvec.len() {
+            continue;
+        }
         let least_common = 2 * column_sum < bitvec.len();
 
         // remove all elements that have the most commont element
@@ -75,55 +74,76 @@ fn find_co2_scrubber(bitvec: &mut Vec<Vec<bool>>) {
     }
 }
 
-pub fn run() {
-    // load data
-    let input = load_input_for_day(3);
-    let data = parse_input(input);
-    let half_height = data.len() / 2;
-    // --------------------------- Task 1 ------------------------------
-    // calculate the line width
-    let width = data[0].len();
-    let mut sums: Vec<usize> = vec![0; width];
-    // got through each line and add to the sum
-    for line in data.iter() {
-        for (i, digit) in line.iter().enumerate() {
-            // check the digit
-            match digit {
-                '0' => {}
-                '1' => {
-                    let sum = sums.get_mut(i).expect("Lines not of the same length!");
-                    *sum += 1;
+pub struct Day3 {
+    data: Vec<Vec<bool>>,
+}
+
+impl Solution for Day3 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day3 {
+            data: parse_input(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let data = &self.data;
+        let half_height = data.len() / 2;
+        // calculate the line width
+        let width = data[0].len();
+        let mut sums: Vec<usize> = vec![0; width];
+        // got through each line and add to the sum
+        for line in data.iter() {
+            for (i, digit) in line.iter().enumerate() {
+                if *digit {
+                    sums[i] += 1;
                 }
-                _ => panic!("Invalid input data! Contains characters that are not eather 1 or 0."),
             }
         }
+        // check if 1 is the most occuring number by checking if the sum is greater than half of the numbers
+        let epsilon_vec: Vec<bool> = sums
+            .iter()
+            .map(|column_sum| *column_sum > half_height)
+            .collect();
+        let gamma_vec: Vec<bool> = epsilon_vec.iter().map(|bit| !bit).collect();
+        // convert from to integer
+        let gamma = bitvec_to_usize(&gamma_vec);
+        let epsilon = bitvec_to_usize(&epsilon_vec);
+        Ok(out(1)
+            .var("gamma", gamma)
+            .var("epsilon", epsilon)
+            .answer("product", gamma * epsilon))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let mut oxygen_generator_data = self.data.clone();
+        let mut co2_scrubber_data = self.data.clone();
+        find_oxygen_generator(&mut oxygen_generator_data);
+        find_co2_scrubber(&mut co2_scrubber_data);
+        let (oxygen_generator_data, co2_scrubber_data) =
+            match (oxygen_generator_data.first(), co2_scrubber_data.first()) {
+                (Some(oxygen), Some(co2)) => (oxygen, co2),
+                _ => return Err(AocError::solve("No rating is left after filtering!")),
+            };
+        let oxygen_generator_rating = bitvec_to_usize(oxygen_generator_data);
+        let co2_scrubber_data = bitvec_to_usize(co2_scrubber_data);
+        Ok(out(2)
+            .var("Oxygen Generator Data", oxygen_generator_rating)
+            .var("CO2 Scrubber Data", co2_scrubber_data)
+            .answer("Product", oxygen_generator_rating * co2_scrubber_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day3;
+    use crate::solution::Solution;
+
+    /// a column in which all rows agree used to remove every row from the co2 candidates
+    #[test]
+    fn co2_rating_skips_columns_without_a_least_common_bit() {
+        let day = Day3::parse("000\n001\n011").unwrap();
+        let output = day.part2().unwrap();
+        // oxygen 001, co2 011
+        assert_eq!(output.answer_value().as_deref(), Some("3"));
     }
-    // check if 1 is the most occuring number by checking if the sum is greater than half of the numbers
-    let epsilon_vec: Vec<bool> = sums
-        .iter()
-        .map(|column_sum| *column_sum > half_height)
-        .collect();
-    let gamma_vec: Vec<bool> = epsilon_vec.iter().map(|bit| !bit).collect();
-    // convert from to integer
-    let gamma = bitvec_to_usize(&gamma_vec);
-    let epsilon = bitvec_to_usize(&epsilon_vec);
-    out(1)
-        .var("gamma", gamma)
-        .var("epsilon", epsilon)
-        .var("product", gamma * epsilon)
-        .print();
-    // --------------------------- Task 2 ----------------------------
-    // convert input data to bitvecs
-    let bitvec_input: Vec<Vec<bool>> = data.iter().map(| row | charvec_to_bitvec(row)).collect();
-    let mut oxygen_generator_data = bitvec_input.clone();
-    let mut co2_scrubber_data = bitvec_input;
-    find_oxygen_generator(&mut oxygen_generator_data);
-    find_co2_scrubber(&mut co2_scrubber_data);
-    let oxygen_generator_rating = bitvec_to_usize(&oxygen_generator_data[0]);
-    let co2_scrubber_data = bitvec_to_usize(&co2_scrubber_data[0]);
-    out(2)
-        .var("Oxygen Generator Data", oxygen_generator_rating)
-        .var("CO2 Scrubber Data", co2_scrubber_data)
-        .var("Product", oxygen_generator_rating * co2_scrubber_data)
-        .print();
 }
@@ -1,5 +1,11 @@
-use crate::helper::{load_input_for_day, out};
+use nom::combinator::consumed;
 
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{comma_list, lines, number, parse_all, sections, space_list, two_sections};
+use crate::solution::Solution;
+
+#[derive(Clone)]
 struct Field {
     pub value: usize,
     pub marked: bool,
@@ -14,6 +20,7 @@ impl Field {
     }
 }
 
+#[derive(Clone)]
 struct Board {
     grid: Vec<Vec<Field>>,
     pub won: bool,
@@ -26,19 +33,20 @@ trait Bingo {
 }
 
 impl Board {
-    /// generates a new bingo board from text input
-    pub fn new(in_lines: &[&str]) -> Board {
-        let mut grid = Vec::<Vec<Field>>::with_capacity(in_lines.len());
-        for line in in_lines {
-            let numbers: Vec<Field> = line
-                .trim()
-                .split_whitespace()
-                .map(|num| Field::new(num.parse().unwrap()))
-                .collect();
-            grid.push(numbers)
+    /// generates a new bingo board from its rows of numbers
+    ///
+    /// each row comes with its line, which has to be a slice of the puzzle `input`
+    pub fn new(input: &str, rows: Vec<(&str, Vec<usize>)>) -> Result<Board, AocError> {
+        let size = rows.len();
+        let mut grid = Vec::<Vec<Field>>::with_capacity(size);
+        for (line, numbers) in rows {
+            if numbers.len() != size {
+                return Err(AocError::parse(input, line, "Bingo board is not square!"));
+            }
+            grid.push(numbers.into_iter().map(Field::new).collect())
         }
 
-        Board { grid, won: false }
+        Ok(Board { grid, won: false })
     }
 }
 
@@ -90,46 +98,64 @@ impl Bingo for Board {
     }
 }
 
-pub fn run() {
-    let input = load_input_for_day(4);
-    // read the draws
-    let mut lines = input.split('\n');
-    let draws = lines.next().unwrap();
-    let draws = draws.split(',').map(|s| s.parse::<usize>().unwrap());
-    lines.next();
-    // list of bingo boards
-    let mut boards = Vec::<Board>::new();
-    // read the bingo boards
-    let mut bingo_lines: Vec<&str> = Vec::new();
-    for line in lines {
-        match line {
-            "" => {
-                boards.push(Board::new(&bingo_lines));
-                bingo_lines = Vec::new();
-            }
-            numbers => bingo_lines.push(numbers),
-        }
-    }
-    let mut board_count = 0;
-    let num_boards = boards.len();
-    for draw in draws {
-        // check numbers
-        for board in boards.iter_mut() {
-            if board.won {
-                continue;
-            }
-            board.number_drawn(draw);
-            if board.check_win() {
-                let unmarked_sum = board.sum_unmarked();
-                if board_count == 0 || board_count == num_boards - 1 {
-                    out(1)
-                        .var("sum", unmarked_sum)
-                        .var("current draw", draw)
-                        .var("product", draw * unmarked_sum)
-                        .print()
+pub struct Day4 {
+    draws: Vec<usize>,
+    boards: Vec<Board>,
+}
+
+impl Day4 {
+    /// plays bingo until all boards have won and returns the
+    /// draw and the sum of unmarked fields for each board
+    /// in the order in which they won
+    fn play(&self) -> Vec<(usize, usize)> {
+        let mut boards = self.boards.clone();
+        let mut wins = Vec::new();
+        for &draw in self.draws.iter() {
+            // check numbers
+            for board in boards.iter_mut() {
+                if board.won {
+                    continue;
+                }
+                board.number_drawn(draw);
+                if board.check_win() {
+                    wins.push((draw, board.sum_unmarked()));
                 }
-                board_count += 1;
             }
         }
+        wins
+    }
+}
+
+impl Solution for Day4 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let board = lines(consumed(space_list(number)));
+        let (draws, boards) = parse_all(input, two_sections(comma_list(number), sections(board)))?;
+        let boards = boards
+            .into_iter()
+            .map(|rows| Board::new(input, rows))
+            .collect::<Result<Vec<Board>, AocError>>()?;
+        Ok(Day4 { draws, boards })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let wins = self.play();
+        let (draw, unmarked_sum) = *wins
+            .first()
+            .ok_or_else(|| AocError::solve("No board has won!"))?;
+        Ok(out(1)
+            .var("sum", unmarked_sum)
+            .var("current draw", draw)
+            .answer("product", draw * unmarked_sum))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let wins = self.play();
+        let (draw, unmarked_sum) = *wins
+            .last()
+            .ok_or_else(|| AocError::solve("No board has won!"))?;
+        Ok(out(2)
+            .var("sum", unmarked_sum)
+            .var("current draw", draw)
+            .answer("product", draw * unmarked_sum))
     }
 }
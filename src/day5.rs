@@ -1,7 +1,17 @@
 use std::collections::HashMap;
+use std::iter;
 
-use crate::helper::{load_input_for_day, out};
-use regex;
+use nom::{character::complete::char, combinator::consumed, sequence::separated_pair};
+
+use crate::error::AocError;
+use crate::grid::Grid;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{arrow, lines, number, parse_all};
+use crate::solution::Solution;
+use crate::visualize::{Cell, Frame, Highlight, Visualize};
+
+/// the lines are drawn in batches, so there are at most this many of them
+const VISUALIZED_BATCHES: usize = 24;
 
 #[derive(PartialEq, Debug)]
 enum Orient {
@@ -34,7 +44,8 @@ impl Line {
         match orient {
             Orient::Horizontal | Orient::Vertical => {
                 // interpolate the points
-                points = (x_range[0]..=x_range[1]).flat_map(|x| (y_range[0]..=y_range[1]).map(move |y| [x, y]))
+                points = (x_range[0]..=x_range[1])
+                    .flat_map(|x| (y_range[0]..=y_range[1]).map(move |y| [x, y]))
                     .collect();
             }
             Orient::Diagonal => {
@@ -75,21 +86,21 @@ struct CoordinateSystem {
     lines: Vec<Line>,
 }
 impl CoordinateSystem {
-    pub fn from_string(lines: String) -> CoordinateSystem {
-        let line_regex = regex::Regex::new(r"(\d+),(\d+) -> (\d+),(\d+)").unwrap();
-        let points = lines.split('\n').map(|line| {
-            // extract all coordinates
-            let re_match = line_regex.captures(line).expect("Invalid line in input!");
-            let mut re_match = re_match.iter();
-            re_match.next().unwrap();
-            let captures: Vec<u32> = re_match
-                .map(|m| m.unwrap().as_str().parse::<u32>().unwrap())
-                .collect();
-            Line::new([captures[0], captures[1]], [captures[2], captures[3]])
+    pub fn from_string(input: &str) -> Result<CoordinateSystem, AocError> {
+        let point = || separated_pair(number::<u32>, char(','), number::<u32>);
+        let segments = parse_all(input, lines(consumed(arrow(point(), point()))))?;
+        let lines = segments.into_iter().map(|(line, ((x1, y1), (x2, y2)))| {
+            let (p1, p2) = ([x1, y1], [x2, y2]);
+            // diagonal lines always have an angle of 45°
+            let is_straight = p1[0] == p2[0] || p1[1] == p2[1];
+            if !is_straight && p1[0].abs_diff(p2[0]) != p1[1].abs_diff(p2[1]) {
+                return Err(AocError::parse(input, line, "Line is not at a 45° angle!"));
+            }
+            Ok(Line::new(p1, p2))
         });
-        CoordinateSystem {
-            lines: points.collect(),
-        }
+        Ok(CoordinateSystem {
+            lines: lines.collect::<Result<Vec<Line>, AocError>>()?,
+        })
     }
     /// returns a HashMap of all the intersections with the key
     /// being the coordinate and the value being the number of
@@ -108,24 +119,115 @@ impl CoordinateSystem {
     }
 }
 
-pub fn run() {
-    let input = load_input_for_day(5);
-    let coordinate_system = CoordinateSystem::from_string(input);
-    // println!("{:?}", coordinate_system);
-    let intersections_no_diagonals = coordinate_system.find_intersections(true);
-    // println!("{:?}", intersections);
-    let count1 = intersections_no_diagonals
-        .values()
-        .filter(|intersection_count| **intersection_count > 1)
-        .count();
-    let intersections = coordinate_system.find_intersections(false);
-    // println!("{:?}", intersections);
-    let count2 = intersections
-        .values()
-        .filter(|intersection_count| **intersection_count > 1)
-        .count();
-    out(1)
-        .var("line overlaps (2 or more, without diagonals)", count1)
-        .print();
-    out(2).var("line overlaps (2 or more)", count2).print();
+/// the number of lines on every point, highlighting the points of `batch`
+/// and the overlaps
+fn overlap_frame(counts: &Grid<u32>, batch: &[Line], caption: String) -> Frame {
+    let mut cells = counts.map(|&count| match count {
+        0 => Cell::new('.'),
+        _ => Cell {
+            symbol: char::from_digit(count.min(9), 10).unwrap(),
+            highlight: match count {
+                1 => Highlight::None,
+                _ => Highlight::Marked,
+            },
+        },
+    });
+    for point in batch.iter().flat_map(|line| line.points.iter()) {
+        cells[(point[0] as usize, point[1] as usize)].highlight = Highlight::Focus;
+    }
+    Frame::new(caption, cells)
+}
+
+pub struct Day5 {
+    coordinate_system: CoordinateSystem,
+}
+
+impl Visualize for Day5 {
+    /// the overlaps of all lines (as in part 2) while they are drawn
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let lines = &self.coordinate_system.lines;
+        let points = lines.iter().flat_map(|line| line.points.iter());
+        let width = points.clone().map(|p| p[0] as usize + 1).max().unwrap_or(0);
+        let height = points.map(|p| p[1] as usize + 1).max().unwrap_or(0);
+        let mut counts = Grid::new(width, height, 0);
+        let caption = |counts: &Grid<u32>, drawn| {
+            let overlaps = counts.iter().filter(|(_, &count)| count > 1).count();
+            format!("{} of {} lines, {} overlaps", drawn, lines.len(), overlaps)
+        };
+        let first = overlap_frame(&counts, &[], caption(&counts, 0));
+        let mut batches = lines.chunks(lines.len().div_ceil(VISUALIZED_BATCHES).max(1));
+        let mut drawn = 0;
+        let mut done = false;
+        let steps = iter::from_fn(move || {
+            let batch = match batches.next() {
+                Some(batch) => batch,
+                // the overlaps once more, without the last lines standing out
+                None if !done => {
+                    done = true;
+                    &[]
+                }
+                None => return None,
+            };
+            for point in batch.iter().flat_map(|line| line.points.iter()) {
+                counts[(point[0] as usize, point[1] as usize)] += 1;
+            }
+            drawn += batch.len();
+            Some(overlap_frame(&counts, batch, caption(&counts, drawn)))
+        });
+        Box::new(iter::once(first).chain(steps))
+    }
+}
+
+impl Solution for Day5 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day5 {
+            coordinate_system: CoordinateSystem::from_string(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let intersections_no_diagonals = self.coordinate_system.find_intersections(true);
+        let count1 = intersections_no_diagonals
+            .values()
+            .filter(|intersection_count| **intersection_count > 1)
+            .count();
+        Ok(out(1).answer("line overlaps (2 or more, without diagonals)", count1))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let intersections = self.coordinate_system.find_intersections(false);
+        let count2 = intersections
+            .values()
+            .filter(|intersection_count| **intersection_count > 1)
+            .count();
+        Ok(out(2).answer("line overlaps (2 or more)", count2))
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day5;
+    use crate::{
+        fixtures::fixture,
+        helper::normalize,
+        solution::Solution,
+        visualize::{Highlight, Visualize},
+    };
+
+    #[test]
+    fn frames_count_overlaps() {
+        let day = Day5::parse(&normalize(fixture(5).unwrap().input)).unwrap();
+        let frames: Vec<_> = day.frames().collect();
+        // a frame per line, as there are less lines than batches
+        assert_eq!(frames.len(), 12);
+        assert_eq!(frames[1].highlighted(Highlight::Focus).count(), 6);
+        let last = frames.last().unwrap();
+        assert_eq!(last.caption, "10 of 10 lines, 12 overlaps");
+        assert_eq!(last.highlighted(Highlight::Marked).count(), 12);
+        assert_eq!(last.to_string().lines().nth(1), Some("1.1....11."));
+    }
 }
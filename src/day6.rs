@@ -1,6 +1,11 @@
 use std::{collections::VecDeque, ops::AddAssign};
 
-use crate::helper::{load_input_for_day, out};
+use nom::combinator::consumed;
+
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{comma_list, number, parse_all};
+use crate::solution::Solution;
 
 /// simulates a the flock of laternfish
 ///
@@ -24,35 +29,43 @@ where
 
 /// Converts a list of strings representing fish timers
 /// to a VecDeque
-fn instring_to_deque(fishin: &str, timer_states: usize) -> VecDeque<u32> {
-    let fish_list_seperate: Vec<u32> = fishin
-        .split(',')
-        .map(|timer| timer.parse().expect("Input timer is not an integer!"))
-        .collect();
+fn instring_to_deque(fishin: &str, timer_states: usize) -> Result<VecDeque<u32>, AocError> {
     let mut fish_list_fused: VecDeque<u32> = vec![0; timer_states].into();
-    for value in fish_list_seperate {
-        fish_list_fused[value as usize] += 1;
+    for (timer, value) in parse_all(fishin, comma_list(consumed(number::<usize>)))? {
+        *fish_list_fused
+            .get_mut(value)
+            .ok_or_else(|| AocError::parse(fishin, timer, "Timer is out of range!"))? += 1;
     }
-    fish_list_fused
+    Ok(fish_list_fused)
+}
+
+pub struct Day6 {
+    fused_fish: VecDeque<u32>,
 }
 
-pub fn run() {
-    // let input = "3,4,3,1,2";
-    let input = load_input_for_day(6);
-    let mut fused_fish = instring_to_deque(&input, 9);
-    let mut fused_fish_task2: VecDeque<u64> = fused_fish
-        .clone()
-        .iter()
-        .map(|num_fish| *num_fish as u64)
-        .collect();
-    simulate_fish(&mut fused_fish, 80);
-    let sum: u32 = fused_fish.iter().sum();
-    out(1).var("sum of fish after 80 days", sum).print();
-    simulate_fish(&mut fused_fish_task2, 256);
-
-    let mut sum_task_2: u64 = 0;
-    fused_fish_task2
-        .iter()
-        .for_each(|num_fish| sum_task_2 += *num_fish as u64);
-    out(2).var("sum of fish after 256 days", sum_task_2).print();
+impl Solution for Day6 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day6 {
+            fused_fish: instring_to_deque(input, 9)?,
+        })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let mut fused_fish = self.fused_fish.clone();
+        simulate_fish(&mut fused_fish, 80);
+        let sum: u32 = fused_fish.iter().sum();
+        Ok(out(1).answer("sum of fish after 80 days", sum))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let mut fused_fish_task2: VecDeque<u64> = self
+            .fused_fish
+            .iter()
+            .map(|num_fish| *num_fish as u64)
+            .collect();
+        simulate_fish(&mut fused_fish_task2, 256);
+
+        let sum_task_2: u64 = fused_fish_task2.iter().sum();
+        Ok(out(2).answer("sum of fish after 256 days", sum_task_2))
+    }
 }
@@ -1,4 +1,7 @@
-use crate::helper::{load_input_for_day, out};
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{comma_list, number, parse_all};
+use crate::solution::Solution;
 
 /// just a simple struct to hold the data of a possible crab
 /// position
@@ -7,79 +10,63 @@ struct Target {
     pub cost: u32,
 }
 
-pub fn run() {
-    // let input = "16,1,2,0,4,2,7,1,2,14";
-    let input = load_input_for_day(7);
-    let input: Vec<u32> = input
-        .split(',')
-        .map(|crab_pos| crab_pos.parse().expect("Input number is not a number"))
-        .collect();
-    let max = input.iter().max().unwrap();
-    let mut min_cost_t1: Option<Target> = None;
-    let mut min_cost_t2: Option<Target> = None;
-    // for each possible position, calculate the cost and
-    // see if it is smaller than the cost of the last value
-    for v in 0..=*max {
-        let mut cost_t1: u32 = 0;
-        let mut cost_t2: u32 = 0;
-        for value in input.iter() {
-            // added cost for task 1 is just the distance
-            let added_cost = (v as i32 - *value as i32).abs() as u32;
-            cost_t1 += added_cost;
-            // for task 2 each step costs 1 fuel more
-            // e.g. for distance 5, the cost would be 5 + 4 + 3 + 2 + 1 = 15
-            // if you draw these numbers as a triangle, you can calculate its are
-            // 5*5/2 = 12.5. Afterwards, you still need to add the remaining
-            // half squares: + 5/2. This boils down to 5*6/2
-            cost_t2 += added_cost * (added_cost + 1) / 2;
-        }
-        match &mut min_cost_t1 {
-            Some(target) => {
-                if target.cost > cost_t1 {
-                    min_cost_t1 = Some(Target {
-                        pos: v,
-                        cost: cost_t1,
-                    });
-                }
-            }
-            None => {
-                min_cost_t1 = Some(Target {
-                    pos: v,
-                    cost: cost_t1,
-                })
-            }
-        }
-        match &mut min_cost_t2 {
-            Some(target) => {
-                if target.cost > cost_t2 {
-                    min_cost_t2 = Some(Target {
-                        pos: v,
-                        cost: cost_t2,
-                    });
-                }
-            }
-            None => {
-                min_cost_t2 = Some(Target {
-                    pos: v,
-                    cost: cost_t2,
-                })
+pub struct Day7 {
+    crabs: Vec<u32>,
+}
+
+impl Day7 {
+    /// for each possible position, calculate the cost and
+    /// see if it is smaller than the cost of the last value
+    ///
+    /// `fuel` converts the distance a crab has to move into
+    /// the fuel it needs for that
+    fn cheapest_target(&self, fuel: impl Fn(u32) -> u32) -> Option<Target> {
+        let max = self.crabs.iter().max()?;
+        let mut min_cost: Option<Target> = None;
+        for v in 0..=*max {
+            let cost: u32 = self
+                .crabs
+                .iter()
+                .map(|value| fuel(v.abs_diff(*value)))
+                .sum();
+            match &min_cost {
+                Some(target) if target.cost <= cost => {}
+                _ => min_cost = Some(Target { pos: v, cost }),
             }
         }
+        min_cost
     }
-    if let Some(target) = min_cost_t1 {
-        out(1)
+}
+
+fn target_output(task: usize, target: Option<Target>) -> OutputFormatter {
+    match target {
+        Some(target) => out(task)
             .var("target position", target.pos)
-            .var("cost", target.cost)
-            .print();
-    } else {
-        out(1).var("No target value could be found", ":(").print();
+            .answer("cost", target.cost),
+        None => out(task).var("No target value could be found", ":("),
     }
-    if let Some(target) = min_cost_t2 {
-        out(2)
-            .var("target position", target.pos)
-            .var("cost", target.cost)
-            .print();
-    } else {
-        out(1).var("No target value could be found", ":(").print();
+}
+
+impl Solution for Day7 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let crabs = parse_all(input, comma_list(number))?;
+        Ok(Day7 { crabs })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        // the cost for task 1 is just the distance
+        Ok(target_output(1, self.cheapest_target(|distance| distance)))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        // for task 2 each step costs 1 fuel more
+        // e.g. for distance 5, the cost would be 5 + 4 + 3 + 2 + 1 = 15
+        // if you draw these numbers as a triangle, you can calculate its are
+        // 5*5/2 = 12.5. Afterwards, you still need to add the remaining
+        // half squares: + 5/2. This boils down to 5*6/2
+        Ok(target_output(
+            2,
+            self.cheapest_target(|distance| distance * (distance + 1) / 2),
+        ))
     }
 }
@@ -2,10 +2,17 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ops::Index;
 
-use regex::Regex;
+use nom::{
+    bytes::complete::{tag, take_while1},
+    combinator::verify,
+    error::context,
+    sequence::separated_pair,
+};
 
-use crate::helper::load_input_for_day;
-use crate::helper::out;
+use crate::error::AocError;
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{lines, parse_all, space_list, PResult};
+use crate::solution::Solution;
 
 #[derive(Clone, Debug)]
 struct Digit {
@@ -73,12 +80,11 @@ fn count_repetitions(digits: &[Digit]) -> HashMap<char, u32> {
 }
 
 /// tries to compute a mapping, mapping each wrong segment input to the correct digit
-///  this is not always possible and if it fails, the outputted HashMap will have missing
-///  keys
+///  this is not always possible and if it fails, `None` is returned
 fn calculate_mapping(
     true_digits: &[Digit; 10],
     unique_patterns: &[HashSet<char>],
-) -> Mapping {
+) -> Option<Mapping> {
     let mut mapping = Mapping::new();
     let trans = unique_patterns
         .iter()
@@ -104,68 +110,70 @@ fn calculate_mapping(
     // a: 8, b: 6, c: 8, d: 7, e: 4, f: 9, g: 7
     //
     // e, b and f are unique
-    repetition_map.iter().for_each(|(c, r)| match *r {
-        4 => {
-            character_map.insert(*c, 'e');
-        }
-        6 => {
-            character_map.insert(*c, 'b');
-        }
-        7 => {
-            // two possibilities: g or d
-            // as d is present in 4 (which we already computed) we can determine the right mapping
-            match &mut digit4data {
-                Some(_) => {}
-                None => digit4data = mapping.mappings.iter().find(|(_set, d)| d.i == 4),
+    for (c, r) in repetition_map.iter() {
+        match *r {
+            4 => {
+                character_map.insert(*c, 'e');
             }
-            let (charset, _digit4) = digit4data.unwrap();
-            match charset.contains(c) {
-                true => {
-                    character_map.insert(*c, 'd');
+            6 => {
+                character_map.insert(*c, 'b');
+            }
+            7 => {
+                // two possibilities: g or d
+                // as d is present in 4 (which we already computed) we can determine the right mapping
+                match &mut digit4data {
+                    Some(_) => {}
+                    None => digit4data = mapping.mappings.iter().find(|(_set, d)| d.i == 4),
                 }
-                false => {
-                    character_map.insert(*c, 'g');
+                let (charset, _digit4) = digit4data?;
+                match charset.contains(c) {
+                    true => {
+                        character_map.insert(*c, 'd');
+                    }
+                    false => {
+                        character_map.insert(*c, 'g');
+                    }
                 }
             }
-        }
-        8 => {
-            // two possibilities: c or a
-            // as c is present in 1 (which we already computed) we can determine the right mapping
-            match &mut digit1data {
-                Some(_) => {}
-                None => digit1data = mapping.mappings.iter().find(|(_set, d)| d.i == 1),
-            }
-            let (charset, _digit1) = digit1data.unwrap();
-            match charset.contains(c) {
-                true => {
-                    character_map.insert(*c, 'c');
+            8 => {
+                // two possibilities: c or a
+                // as c is present in 1 (which we already computed) we can determine the right mapping
+                match &mut digit1data {
+                    Some(_) => {}
+                    None => digit1data = mapping.mappings.iter().find(|(_set, d)| d.i == 1),
                 }
-                false => {
-                    character_map.insert(*c, 'a');
+                let (charset, _digit1) = digit1data?;
+                match charset.contains(c) {
+                    true => {
+                        character_map.insert(*c, 'c');
+                    }
+                    false => {
+                        character_map.insert(*c, 'a');
+                    }
                 }
             }
+            9 => {
+                character_map.insert(*c, 'f');
+            }
+            _ => {}
         }
-        9 => {
-            character_map.insert(*c, 'f');
-        }
-        _ => {}
-    });
+    }
     // translate the remaining patterns using the charactermap
-    remaining_patterns.iter().for_each(|pattern| {
+    for pattern in remaining_patterns.iter() {
         // println!("Remaining : {}", pattern.iter().collect::<String>());
         let charset = pattern
             .iter()
-            .map(|segment| character_map[segment])
-            .collect::<HashSet<char>>();
-        let digit = true_digits.iter().find(|d| d.segments == charset).unwrap();
+            .map(|segment| character_map.get(segment).copied())
+            .collect::<Option<HashSet<char>>>()?;
+        let digit = true_digits.iter().find(|d| d.segments == charset)?;
         mapping.insert(pattern.clone(), digit.clone());
-    });
-    mapping
+    }
+    Some(mapping)
 }
 
-pub fn run() {
-    // The true segment to digit mapping
-    let true_segment_mapping: [Digit; 10] = [
+/// The true segment to digit mapping
+fn true_segment_mapping() -> [Digit; 10] {
+    [
         Digit::new(0, "abcefg", false),
         Digit::new(1, "cf", true),
         Digit::new(2, "acdeg", false),
@@ -176,56 +184,109 @@ pub fn run() {
         Digit::new(7, "acf", true),
         Digit::new(8, "abcdefg", true),
         Digit::new(9, "abcdfg", false),
-    ];
-
-    // let input =
-    //    "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
-    // println!("{}", input);
-    let input = load_input_for_day(8);
-    let digit_pattern = r"([a-g]+) ?";
-    let extraction_pattern = format!(
-        r"{}\| {}",
-        digit_pattern.repeat(10),
-        digit_pattern.repeat(4)
-    );
-    let extraction_re = Regex::new(&extraction_pattern).unwrap();
-    let mut count1478 = 0;
-    let mut count = 0;
-    // extract information from line
-    for line in input.split('\n') {
-        let captures = extraction_re.captures(line).expect("Invalid input line.");
-        // reminder: first capture is always the whole match
-        let capture_strings: Vec<&str> = captures
-            .iter()
-            .map(|m| m.unwrap().as_str())
-            .skip(1)
-            .collect();
-        let unique_patterns: &Vec<HashSet<char>> = &capture_strings[0..10]
-            .iter()
-            .map(|s| s.chars().collect())
+    ]
+}
+
+/// a single line of the input: the ten unique patterns and
+/// the four digits of the output value
+struct Entry {
+    line: usize,
+    unique_patterns: Vec<HashSet<char>>,
+    digits: Vec<String>,
+}
+
+/// a line like `acedgfb cdfbe ... | cdfeb fcadb cdfeb cdbaf`
+fn entry(input: &str) -> PResult<'_, (Vec<&str>, Vec<&str>)> {
+    let pattern = || {
+        context(
+            "Expected segments a-g",
+            take_while1(|c| ('a'..='g').contains(&c)),
+        )
+    };
+    separated_pair(
+        context(
+            "Expected ten unique patterns",
+            verify(space_list(pattern()), |patterns: &Vec<&str>| {
+                patterns.len() == 10
+            }),
+        ),
+        context("Expected ' | '", tag(" | ")),
+        context(
+            "Expected four output digits",
+            verify(space_list(pattern()), |digits: &Vec<&str>| {
+                digits.len() == 4
+            }),
+        ),
+    )(input)
+}
+
+pub struct Day8 {
+    entries: Vec<Entry>,
+}
+
+impl Day8 {
+    fn mappings(&self) -> impl Iterator<Item = Result<(&Entry, Mapping), AocError>> {
+        let true_segment_mapping = true_segment_mapping();
+        self.entries.iter().map(move |entry| {
+            let mapping = calculate_mapping(&true_segment_mapping, &entry.unique_patterns)
+                .ok_or_else(|| {
+                    AocError::solve(format!(
+                        "The wiring of line {} could not be determined!",
+                        entry.line
+                    ))
+                })?;
+            Ok((entry, mapping))
+        })
+    }
+}
+
+impl Solution for Day8 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let entries = parse_all(input, lines(entry))?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (unique_patterns, digits))| Entry {
+                line: i + 1,
+                unique_patterns: unique_patterns
+                    .iter()
+                    .map(|pattern| pattern.chars().collect())
+                    .collect(),
+                digits: digits.iter().map(|digit| digit.to_string()).collect(),
+            })
             .collect();
-        let digits = &capture_strings[10..14];
-        // calculate mapping
-        let mapping = calculate_mapping(&true_segment_mapping, unique_patterns);
-        let number_of_1478 = digits
-            .iter()
-            .map(|d| mapping.get(d.chars().collect()))
-            .filter(|d| d.is_some() && [1, 4, 7, 8].contains(&d.unwrap().i))
-            .count();
-        let mut number = String::new();
-        // println!("{}", line);
-        for d in digits {
-            // println!("{}", d.chars().collect::<String>());
-            let result = mapping.get(d.chars().collect()).unwrap().i.to_string();
-            number.push_str(&result)
+        Ok(Day8 { entries })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let mut count1478 = 0;
+        for mapping in self.mappings() {
+            let (entry, mapping) = mapping?;
+            count1478 += entry
+                .digits
+                .iter()
+                .filter_map(|d| mapping.get(d.chars().collect()))
+                .filter(|d| [1, 4, 7, 8].contains(&d.i))
+                .count();
         }
+        Ok(out(1).answer("number of times 1, 4, 7 or 8 appeared", count1478))
+    }
 
-        let number = number.parse::<u32>().unwrap();
-        count1478 += number_of_1478;
-        count += number;
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let mut count = 0;
+        for mapping in self.mappings() {
+            let (entry, mapping) = mapping?;
+            let mut number = 0;
+            for d in entry.digits.iter() {
+                let digit = mapping.get(d.chars().collect()).ok_or_else(|| {
+                    AocError::solve(format!(
+                        "The output digit '{}' of line {} is not one of its patterns!",
+                        d, entry.line
+                    ))
+                })?;
+                number = number * 10 + digit.i as u32;
+            }
+            count += number;
+        }
+        Ok(out(2).answer("all output values summed up", count))
     }
-    out(1)
-        .var("number of times 1, 4, 7 or 8 appeared", count1478)
-        .print();
-    out(2).var("all output values summed up", count).print();
 }
@@ -1,92 +1,55 @@
-use crate::helper::{load_input_for_day, out};
+use std::iter;
 
-// which adjacent points should be checked
-const OFFSETS: [[i64; 2]; 4] = [[0i64, -1], [1, 0], [0, 1], [-1, 0]];
+use crate::error::AocError;
+use crate::grid::{Grid, Pos};
+use crate::helper::{out, OutputFormatter};
+use crate::parse::{char_grid, parse_all};
+use crate::solution::Solution;
+use crate::visualize::{Cell, Frame, Highlight, Visualize};
 
 pub struct HeightMap {
-    inner_map: Vec<Vec<u8>>,
-    width: usize,
-    height: usize,
+    inner_map: Grid<u8>,
 }
 
 impl HeightMap {
-    pub fn from_string(string: String) -> HeightMap {
-        let height_map = string
-            .split_whitespace()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as u8)
-                    .collect()
-            })
-            .collect::<Vec<Vec<u8>>>();
-        let (w, h) = (height_map[0].len(), height_map.len());
-        HeightMap {
-            inner_map: height_map,
-            width: w,
-            height: h,
-        }
+    pub fn from_string(string: &str) -> Result<HeightMap, AocError> {
+        let inner_map = parse_all(
+            string,
+            char_grid(
+                |c| c.to_digit(10).map(|digit| digit as u8),
+                "Height is not a digit!",
+            ),
+        )?;
+        Ok(HeightMap { inner_map })
     }
 
-    pub fn get_adjacent<'a>(
-        &'a self,
-        x: usize,
-        y: usize,
-    ) -> Result<impl Iterator<Item = ([usize; 2], &'a u8)>, &'static str> {
-        let in_bounds = x < self.width && y < self.height;
-        if !in_bounds {
-            return Err("Point does not exist");
-        }
+    pub fn find_low_points(&self) -> impl Iterator<Item = (Pos, u8)> + '_ {
+        self.inner_map.iter().filter_map(move |(pos, &point)| {
+            let mut adjacent_points = self.inner_map.neighbors4(pos);
+            let is_low_point = adjacent_points.all(|(_pos, &value)| value > point);
 
-        let adjacent = OFFSETS.iter().filter_map(move |&[off_x, off_y]| {
-            let (x_i64, y_i64) = (x as i64, y as i64);
-            let adjacent_x = x_i64 + off_x;
-            let adjacent_y = y_i64 + off_y;
-            let in_bounds = adjacent_x >= 0
-                && adjacent_x < self.width as i64
-                && adjacent_y >= 0
-                && adjacent_y < self.height as i64;
-            if !in_bounds {
-                return None;
+            match is_low_point {
+                true => Some((pos, point)),
+                false => None,
             }
-            let adjacent = &self.inner_map[adjacent_y as usize][adjacent_x as usize];
-            Some(([adjacent_x as usize, adjacent_y as usize], adjacent))
-        });
-        Ok(adjacent)
-    }
-    pub fn find_low_points(&self) -> impl Iterator<Item = ([usize; 2], u8)> + '_ {
-        (0..self.height).flat_map(move |y| {
-            (0..self.width).filter_map(move |x| {
-                let point = self.inner_map[y as usize][x as usize];
-                let mut adjacent_points = self.get_adjacent(x, y).unwrap();
-                let is_low_point = adjacent_points.all(|(_pos, &value)| value > point);
-
-                match is_low_point {
-                    true => Some(([x, y], point)),
-                    false => None,
-                }
-            })
         })
     }
-    fn basin_around_low_point(&self, x: usize, y: usize) -> Vec<([usize; 2], u8)> {
+    fn basin_around_low_point(&self, pos: Pos) -> Vec<(Pos, u8)> {
         // where has the algorithm already checked if the point is part of the basin?
-        let value = self.inner_map[y][x];
-        let mut points_checked = vec![vec![false; self.width]; self.height];
-        let mut points_to_be_checked = vec![([x, y], value)];
+        let value = self.inner_map[pos];
+        let mut points_checked = Grid::new(self.inner_map.width(), self.inner_map.height(), false);
+        let mut points_to_be_checked = vec![(pos, value)];
         let mut basin = Vec::new();
-        while !points_to_be_checked.is_empty() {
-            // check all neighbours that haven't already been checked
-            let (pos, value) = points_to_be_checked.pop().unwrap();
-            let (x, y) = (pos[0], pos[1]);
-            if points_checked[y][x] {
+        // check all neighbours that haven't already been checked
+        while let Some((pos, value)) = points_to_be_checked.pop() {
+            if points_checked[pos] {
                 continue;
             }
-            for (adj_pos, adj_height) in self.get_adjacent(x, y).unwrap() {
-                let (ax, ay) = (adj_pos[0], adj_pos[1]);
+            for (adj_pos, adj_height) in self.inner_map.neighbors4(pos) {
                 // skip all we have already checked
-                if points_checked[ay][ax] {
+                if points_checked[adj_pos] {
                     continue;
                 }
-                // println!("Adj of {:?} : {:?}", pos, adj_pos);
                 match adj_height {
                     9 => {}
                     _ => {
@@ -95,48 +58,125 @@ impl HeightMap {
                 }
             }
             basin.push((pos, value));
-            points_checked[y][x] = true;
-            // println!("Checked point {} {}", x, y);
-            // println!("{:?}", points_to_be_checked);
+            points_checked[pos] = true;
         }
-        // println!("------------------- Basin for {} {}", x, y);
-        // points_checked.iter().for_each(| line | {
-        //     line.iter().for_each( | c | print!("{}", *c as u8));
-        //     println!("");
-        // });
-        // println!("{:?}", basin);
-        // println!("-------------------------------------");
         basin
     }
     fn find_basins<'a>(
         &'a self,
-        low_points: impl Iterator<Item = ([usize; 2], u8)> + 'a,
-    ) -> impl Iterator<Item = Vec<([usize; 2], u8)>> + 'a {
-        low_points.map(|(p, _value)| self.basin_around_low_point(p[0], p[1]))
+        low_points: impl Iterator<Item = (Pos, u8)> + 'a,
+    ) -> impl Iterator<Item = Vec<(Pos, u8)>> + 'a {
+        low_points.map(|(pos, _value)| self.basin_around_low_point(pos))
+    }
+    /// floods all basins at once, one frame per step away from the low points
+    fn flood_frames(&self) -> impl Iterator<Item = Frame> + '_ {
+        let heights = self
+            .inner_map
+            .map(|height| Cell::new((b'0' + height) as char));
+        let basin_cells = self.inner_map.iter().filter(|(_, &height)| height != 9);
+        let total = basin_cells.count();
+        let mut in_basin = self.inner_map.map(|_| false);
+        let mut front: Vec<Pos> = self.find_low_points().map(|(pos, _)| pos).collect();
+        for &pos in front.iter() {
+            in_basin[pos] = true;
+        }
+        let (mut filled, mut step) = (0, 0);
+        iter::from_fn(move || {
+            if front.is_empty() {
+                return None;
+            }
+            filled += front.len();
+            let mut cells = heights.clone();
+            for (pos, cell) in cells.iter_mut() {
+                if in_basin[pos] {
+                    cell.highlight = Highlight::Marked;
+                }
+            }
+            for &pos in front.iter() {
+                cells[pos].highlight = Highlight::Focus;
+            }
+            let caption = format!(
+                "flood step {}, {} of {} cells in basins",
+                step, filled, total
+            );
+            step += 1;
+            let mut next = Vec::new();
+            for &pos in front.iter() {
+                for (adj_pos, &adj_height) in self.inner_map.neighbors4(pos) {
+                    if adj_height != 9 && !in_basin[adj_pos] {
+                        in_basin[adj_pos] = true;
+                        next.push(adj_pos);
+                    }
+                }
+            }
+            front = next;
+            Some(Frame::new(caption, cells))
+        })
     }
 }
 
-pub fn run() {
-    let input = load_input_for_day(9);
-    let height_map = HeightMap::from_string(input);
-    let low_points: Vec<([usize; 2], u8)> = height_map.find_low_points().collect();
-    // Task 1
+pub struct Day9 {
+    height_map: HeightMap,
+}
+
+impl Visualize for Day9 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        Box::new(self.height_map.flood_frames())
+    }
+}
 
-    let risk: u32 = low_points
-        .iter()
-        .map(|(_pos, value)| 1 + (*value as u32))
-        .sum();
-    out(1).var("risk", risk).print();
-    // Task 2
-    let mut basins: Vec<Vec<([usize; 2], u8)>> =
-        height_map.find_basins(low_points.into_iter()).collect();
-    basins.sort_by_key(|basin| basin.len());
-    basins.reverse();
-    let prod_of_three_largest: u64 = basins[0..3]
-        .iter()
-        .map(|basin| basin.len() as u64)
-        .product();
-    out(2)
-        .var("product of three largest basins", prod_of_three_largest)
-        .print();
+impl Solution for Day9 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day9 {
+            height_map: HeightMap::from_string(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {
+        let risk: u32 = self
+            .height_map
+            .find_low_points()
+            .map(|(_pos, value)| 1 + (value as u32))
+            .sum();
+        Ok(out(1).answer("risk", risk))
+    }
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {
+        let low_points = self.height_map.find_low_points();
+        let mut basins: Vec<Vec<(Pos, u8)>> = self.height_map.find_basins(low_points).collect();
+        basins.sort_by_key(|basin| basin.len());
+        basins.reverse();
+        let prod_of_three_largest: u64 = basins
+            .get(0..3)
+            .ok_or_else(|| AocError::solve("There are less than three basins!"))?
+            .iter()
+            .map(|basin| basin.len() as u64)
+            .product();
+        Ok(out(2).answer("product of three largest basins", prod_of_three_largest))
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day9;
+    use crate::{fixtures::fixture, helper::normalize, solution::Solution, visualize::Highlight};
+
+    #[test]
+    fn flood_fills_every_basin() {
+        let day = Day9::parse(&normalize(fixture(9).unwrap().input)).unwrap();
+        let frames: Vec<_> = day.height_map.flood_frames().collect();
+        // the low points come first
+        assert_eq!(frames[0].highlighted(Highlight::Focus).count(), 4);
+        let last = frames.last().unwrap();
+        assert_eq!(
+            last.caption,
+            format!("flood step {}, 35 of 35 cells in basins", frames.len() - 1)
+        );
+        // only the nines are left
+        assert_eq!(last.highlighted(Highlight::None).count(), 15);
+    }
 }
@@ -0,0 +1,191 @@
+use std::{error::Error, fmt, str::FromStr, time::Duration};
+
+use crate::helper::InputError;
+
+/// Position of an error in the puzzle input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// line number, starting at 1
+    pub line: usize,
+    /// column (in characters), starting at 1
+    pub column: usize,
+    /// the content of the line
+    pub text: String,
+}
+
+impl Location {
+    /// finds the position of `fragment` inside of `input`.
+    ///
+    /// `fragment` has to be a slice of `input` (as returned by `lines`,
+    /// `split`, `trim`, ...), otherwise `None` is returned.
+    pub fn of(input: &str, fragment: &str) -> Option<Location> {
+        let start = input.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize).checked_sub(start)?;
+        if offset + fragment.len() > input.len() {
+            return None;
+        }
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Some(Location {
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        writeln!(f, "{} | {}", number, self.text)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+/// The error type shared by all days
+#[derive(Debug)]
+pub enum AocError {
+    /// the input file could not be loaded
+    Input(InputError),
+    /// the input does not have the expected format
+    Parse {
+        day: Option<usize>,
+        location: Option<Location>,
+        message: String,
+    },
+    /// the input could be parsed, but does not have a solution
+    Solve { day: Option<usize>, message: String },
+    /// the solution panicked, e.g. because it is not finished yet
+    Panic { day: Option<usize>, message: String },
+    /// the solution did not finish within the time limit
+    Timeout { day: Option<usize>, limit: Duration },
+}
+
+impl AocError {
+    /// a parse error at the position of `fragment`, which has to be a slice of `input`
+    pub fn parse(input: &str, fragment: &str, message: impl Into<String>) -> AocError {
+        AocError::Parse {
+            day: None,
+            location: Location::of(input, fragment),
+            message: message.into(),
+        }
+    }
+    /// a parse error that can not be attributed to a position in the input
+    pub fn invalid(message: impl Into<String>) -> AocError {
+        AocError::Parse {
+            day: None,
+            location: None,
+            message: message.into(),
+        }
+    }
+    pub fn solve(message: impl Into<String>) -> AocError {
+        AocError::Solve {
+            day: None,
+            message: message.into(),
+        }
+    }
+    /// attaches the day the error occurred in
+    pub fn in_day(mut self, number: usize) -> AocError {
+        match &mut self {
+            AocError::Input(_) => {}
+            AocError::Parse { day, .. }
+            | AocError::Solve { day, .. }
+            | AocError::Panic { day, .. }
+            | AocError::Timeout { day, .. } => *day = Some(number),
+        }
+        self
+    }
+    pub fn day(&self) -> Option<usize> {
+        match self {
+            AocError::Input(InputError::Missing { day, .. })
+            | AocError::Input(InputError::NoExample { day }) => Some(*day),
+            AocError::Input(_) => None,
+            AocError::Parse { day, .. }
+            | AocError::Solve { day, .. }
+            | AocError::Panic { day, .. }
+            | AocError::Timeout { day, .. } => *day,
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (Some(day), false) = (self.day(), matches!(self, AocError::Input(_))) {
+            write!(f, "day {}: ", day)?;
+        }
+        match self {
+            AocError::Input(err) => write!(f, "{}", err),
+            AocError::Parse {
+                location: Some(location),
+                message,
+                ..
+            } => write!(
+                f,
+                "line {}, column {}: {}\n{}",
+                location.line, location.column, message, location
+            ),
+            AocError::Parse { message, .. } => write!(f, "invalid input: {}", message),
+            AocError::Solve { message, .. } => write!(f, "{}", message),
+            AocError::Panic { message, .. } => write!(f, "panicked: {}", message),
+            AocError::Timeout { limit, .. } => write!(f, "timed out after {:?}", limit),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Input(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<InputError> for AocError {
+    fn from(err: InputError) -> Self {
+        AocError::Input(err)
+    }
+}
+
+/// parses `fragment` (a slice of `input`) into a number
+pub fn parse_number<T: FromStr>(input: &str, fragment: &str) -> Result<T, AocError> {
+    fragment
+        .parse()
+        .map_err(|_| AocError::parse(input, fragment, format!("'{}' is not a number", fragment)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_number, AocError, Location};
+
+    #[test]
+    fn locate_fragment() {
+        let input = "12\n3x4\n5";
+        let line = input.lines().nth(1).unwrap();
+        let location = Location::of(input, &line[1..]).unwrap();
+        assert_eq!((location.line, location.column), (2, 2));
+        assert_eq!(location.text, "3x4");
+        assert_eq!(Location::of(input, "not in the input"), None);
+    }
+
+    #[test]
+    fn display_parse_error() {
+        let input = "1\n2\nthree";
+        let line = input.lines().last().unwrap();
+        let err = parse_number::<u32>(input, line).unwrap_err().in_day(1);
+        assert_eq!(
+            err.to_string(),
+            "day 1: line 3, column 1: 'three' is not a number\n3 | three\n  | ^"
+        );
+        assert_eq!(
+            AocError::solve("no board has won").in_day(4).to_string(),
+            "day 4: no board has won"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    image::{write_png, write_ppm, Gif, Image, Rgb},
+    visualize::{Cell, Frame, Highlight, Visualize},
+};
+
+/// Colors of the exported images, digits are shaded from the background
+/// to the foreground (or the marked color), `#` is drawn like a 9
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    pub focus: Rgb,
+    pub marked: Rgb,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::DARK
+    }
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        background: [0x0f, 0x0f, 0x23],
+        foreground: [0xe6, 0xe6, 0xe6],
+        focus: [0xff, 0xd7, 0x00],
+        marked: [0x00, 0x99, 0xcc],
+    };
+    pub const LIGHT: Palette = Palette {
+        background: [0xff, 0xff, 0xff],
+        foreground: [0x20, 0x20, 0x20],
+        focus: [0xd0, 0x30, 0x20],
+        marked: [0x20, 0x60, 0xc0],
+    };
+    pub const GRAY: Palette = Palette {
+        background: [0x00, 0x00, 0x00],
+        foreground: [0xb0, 0xb0, 0xb0],
+        focus: [0xff, 0xff, 0xff],
+        marked: [0x70, 0x70, 0x70],
+    };
+
+    /// the colors of the 10 levels of plain, focused and marked cells
+    fn colors(&self) -> Vec<Rgb> {
+        let mut colors = Vec::new();
+        for highlight in [Highlight::None, Highlight::Focus, Highlight::Marked] {
+            colors.extend((0..LEVELS).map(|level| match highlight {
+                Highlight::None => mix(self.background, self.foreground, shade(level)),
+                Highlight::Focus => self.focus,
+                Highlight::Marked => {
+                    let dim = mix(self.background, self.marked, 0.4);
+                    mix(dim, self.marked, level as f32 / 9.0)
+                }
+            }));
+        }
+        colors
+    }
+}
+
+fn parse_color(color: &str) -> Option<Rgb> {
+    let color = color.strip_prefix('#').unwrap_or(color);
+    if color.len() != 6 || !color.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    /// a named palette, or four colors `background,foreground,focus,marked` like `#102030`
+    fn from_str(palette: &str) -> Result<Self, Self::Err> {
+        match palette {
+            "dark" => return Ok(Palette::DARK),
+            "light" => return Ok(Palette::LIGHT),
+            "gray" => return Ok(Palette::GRAY),
+            _ => {}
+        }
+        let colors: Option<Vec<Rgb>> = palette.split(',').map(parse_color).collect();
+        match colors.as_deref() {
+            Some(&[background, foreground, focus, marked]) => Ok(Palette {
+                background,
+                foreground,
+                focus,
+                marked,
+            }),
+            _ => Err(format!("Invalid palette: '{}'", palette)),
+        }
+    }
+}
+
+const LEVELS: u8 = 10;
+
+/// how bright a digit (or `#` for the brightest) is drawn
+fn level(symbol: char) -> u8 {
+    match symbol {
+        '#' => LEVELS - 1,
+        _ => symbol.to_digit(10).map_or(0, |digit| digit as u8),
+    }
+}
+
+/// how far the color of a plain cell is from the background, even level 1
+/// stands out
+fn shade(level: u8) -> f32 {
+    match level {
+        0 => 0.0,
+        _ => 0.3 + 0.7 * (level as f32 / 9.0),
+    }
+}
+
+fn mix(from: Rgb, to: Rgb, amount: f32) -> Rgb {
+    let channel = |i: usize| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * amount).round();
+    [channel(0) as u8, channel(1) as u8, channel(2) as u8]
+}
+
+/// the index of the color of `cell` in `Palette::colors`
+fn color(cell: &Cell) -> u8 {
+    let offset = match cell.highlight {
+        Highlight::None => 0,
+        Highlight::Focus => LEVELS,
+        Highlight::Marked => 2 * LEVELS,
+    };
+    offset + level(cell.symbol)
+}
+
+/// Image file formats frames can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    /// all frames animated
+    Gif,
+}
+
+impl ImageFormat {
+    /// the format matching the extension of `path`
+    pub fn from_path(path: &Path) -> Result<ImageFormat, String> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension
+            .map(|extension| extension.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("ppm") => Ok(ImageFormat::Ppm),
+            Some("png") => Ok(ImageFormat::Png),
+            Some("gif") => Ok(ImageFormat::Gif),
+            _ => Err(format!(
+                "Unknown image format of '{}', use .ppm, .png or .gif",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// cells are drawn this many pixels wide unless a size is given, or smaller
+/// so the image is at most `MAX_DEFAULT_SIDE` pixels wide and high
+const DEFAULT_CELL_SIZE: usize = 8;
+const MAX_DEFAULT_SIDE: usize = 800;
+/// how long each frame of a GIF is shown, in hundredths of a second
+const FRAME_DELAY: u16 = 12;
+/// the last frame stays a little longer before the animation starts over
+const LAST_FRAME_DELAY: u16 = 200;
+
+/// draws `frame` in the top left corner of an image of `width` x `height` cells
+fn render(frame: &Frame, width: usize, height: usize, cell_size: usize) -> Image {
+    let mut image = Image::new(width * cell_size, height * cell_size, 0);
+    for ((x, y), cell) in frame.cells.iter() {
+        image.fill(
+            x * cell_size,
+            y * cell_size,
+            cell_size,
+            cell_size,
+            color(cell),
+        );
+    }
+    image
+}
+
+/// Writes the frames of `visualization` to `path`, as an animated GIF or as
+/// a PPM or PNG still of the last frame. Frames of a GIF that are smaller than
+/// the largest one are drawn in its top left corner.
+pub fn export(
+    visualization: &dyn Visualize,
+    path: &Path,
+    palette: &Palette,
+    cell_size: Option<usize>,
+) -> io::Result<()> {
+    let format = ImageFormat::from_path(path)
+        .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+    let no_frames = || io::Error::new(io::ErrorKind::InvalidInput, "there are no frames");
+    let cell_size = |width: usize, height: usize| {
+        let fitting = MAX_DEFAULT_SIDE / width.max(height).max(1);
+        cell_size.unwrap_or(fitting.clamp(1, DEFAULT_CELL_SIZE))
+    };
+    let colors = palette.colors();
+    if format != ImageFormat::Gif {
+        let last = visualization.frames().last().ok_or_else(no_frames)?;
+        let (width, height) = (last.cells.width(), last.cells.height());
+        let image = render(&last, width, height, cell_size(width, height));
+        let mut out = BufWriter::new(File::create(path)?);
+        match format {
+            ImageFormat::Ppm => write_ppm(&image, &colors, &mut out)?,
+            _ => write_png(&image, &colors, &mut out)?,
+        }
+        return out.flush();
+    }
+    // all frames of a GIF have the same size, which is only known after the last one
+    let (mut width, mut height, mut count) = (0, 0, 0);
+    for frame in visualization.frames() {
+        width = width.max(frame.cells.width());
+        height = height.max(frame.cells.height());
+        count += 1;
+    }
+    if count == 0 {
+        return Err(no_frames());
+    }
+    let cell_size = cell_size(width, height);
+    let out = BufWriter::new(File::create(path)?);
+    let mut gif = Gif::new(out, width * cell_size, height * cell_size, &colors)?;
+    for (i, frame) in visualization.frames().enumerate() {
+        let delay = match i + 1 == count {
+            true => LAST_FRAME_DELAY,
+            false => FRAME_DELAY,
+        };
+        gif.frame(&render(&frame, width, height, cell_size), delay)?;
+    }
+    gif.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{color, render, ImageFormat, Palette, LEVELS};
+    use crate::{
+        grid::Grid,
+        visualize::{Cell, Frame, Highlight},
+    };
+    use std::path::Path;
+
+    #[test]
+    fn palettes() {
+        assert_eq!("light".parse(), Ok(Palette::LIGHT));
+        let custom: Palette = "#000000,ffffff,ff0000,0000FF".parse().unwrap();
+        assert_eq!(custom.focus, [255, 0, 0]);
+        assert_eq!(custom.colors().len(), 3 * LEVELS as usize);
+        assert_eq!(custom.colors()[0], [0, 0, 0]);
+        assert_eq!(custom.colors()[9], [255, 255, 255]);
+        assert!("000000,ffffff".parse::<Palette>().is_err());
+        assert!("rainbow".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn formats_from_extensions() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("day11.GIF")),
+            Ok(ImageFormat::Gif)
+        );
+        assert!(ImageFormat::from_path(Path::new("day11.jpg")).is_err());
+        assert!(ImageFormat::from_path(Path::new("day11")).is_err());
+    }
+
+    #[test]
+    fn cells_become_squares() {
+        let mut cells = Grid::new(2, 1, Cell::new('#'));
+        cells[(0, 0)].highlight = Highlight::Focus;
+        let image = render(&Frame::new("", cells), 3, 1, 2);
+        assert_eq!((image.width, image.height), (6, 2));
+        let focus = color(&Cell {
+            symbol: '#',
+            highlight: Highlight::Focus,
+        });
+        assert_eq!(&image.pixels[..6], [focus, focus, 9, 9, 0, 0]);
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    answers::answer_matches, error::AocError, helper::normalize, registry, scaffold::crate_root,
+};
+
+/// The example from a day's puzzle description together with
+/// the answers given there
+pub struct Fixture {
+    pub day: usize,
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Fixture {
+    pub fn expected(&self, part: u8) -> Option<&'static str> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+}
+
+pub fn fixture(day: usize) -> Option<&'static Fixture> {
+    FIXTURES.iter().find(|fixture| fixture.day == day)
+}
+
+/// `inputs/examples` in the source of the crate
+pub fn examples_dir() -> PathBuf {
+    crate_root().join("inputs").join("examples")
+}
+
+/// the example of `day` in `examples_dir`
+pub fn example_in(examples_dir: &Path, day: usize) -> PathBuf {
+    examples_dir.join(format!("day{}.txt", day))
+}
+
+/// `inputs/examples/dayN.txt` in the source of the crate
+pub fn example_path(day: usize) -> PathBuf {
+    example_in(&examples_dir(), day)
+}
+
+/// the example of a day, read from `example_path` so edits show up without
+/// recompiling, falling back to the example compiled into the fixtures
+pub fn load_example(day: usize) -> Option<String> {
+    fs::read_to_string(example_path(day))
+        .ok()
+        .or_else(|| fixture(day).map(|fixture| fixture.input.to_string()))
+}
+
+/// runs the solution of the fixture's day on the example and returns
+/// the parts whose answer differ from the expected one
+/// as `(part, expected, answer)`
+pub fn check(fixture: &Fixture) -> Result<Vec<(u8, String, Option<String>)>, AocError> {
+    check_input(fixture, fixture.input)
+}
+
+/// like `check`, but runs the solution on `input` (normalized like all inputs)
+pub fn check_input(
+    fixture: &Fixture,
+    input: &str,
+) -> Result<Vec<(u8, String, Option<String>)>, AocError> {
+    let registry = registry();
+    let day = registry
+        .get(fixture.day)
+        .ok_or_else(|| AocError::solve(format!("day {} is not registered", fixture.day)))?;
+    let solution = day.parse(&normalize(input))?;
+    let mut mismatches = Vec::new();
+    for part in [1, 2] {
+        if let Some(expected) = fixture.expected(part) {
+            let answer = day.solve(solution.as_ref(), part)?.answer_value();
+            match &answer {
+                Some(answer) if answer_matches(expected, answer) => {}
+                _ => mismatches.push((part, expected.to_string(), answer)),
+            }
+        }
+    }
+    Ok(mismatches)
+}
+
+/// the example as it might be saved by different editors: with a final line
+/// break, with CRLF line breaks, with trailing spaces and blank lines
+#[cfg(test)]
+fn variations(input: &str) -> Vec<String> {
+    let spaced: Vec<String> = input.lines().map(|line| format!("{}  ", line)).collect();
+    vec![
+        input.to_string(),
+        format!("{}\n", input),
+        format!("{}\r\n", input.replace('\n', "\r\n")),
+        format!("\n{}\n\n\n", spaced.join("\n")),
+    ]
+}
+
+/// registers the example `inputs/examples/dayN.txt` with its answers
+/// and generates a test checking them
+macro_rules! fixtures {
+    ($($test:ident: $day:literal => $part1:expr, $part2:expr;)*) => {
+        pub const FIXTURES: &[Fixture] = &[$(
+            Fixture {
+                day: $day,
+                input: include_str!(concat!("../inputs/examples/day", stringify!($day), ".txt")),
+                part1: $part1,
+                part2: $part2,
+            },
+        )*];
+
+        #[cfg(test)]
+        mod tests {
+            $(
+                #[test]
+                fn $test() {
+                    let fixture = super::fixture($day).unwrap();
+                    for input in super::variations(fixture.input) {
+                        let mismatches = super::check_input(fixture, &input).unwrap();
+                        assert!(
+                            mismatches.is_empty(),
+                            "wrong answers for {:?}: {:?}",
+                            input,
+                            mismatches
+                        );
+                    }
+                }
+            )*
+        }
+    };
+}
+
+fixtures! {
+    day1: 1 => Some("7"), Some("5");
+    day2: 2 => Some("150"), Some("900");
+    day3: 3 => Some("198"), Some("230");
+    day4: 4 => Some("4512"), Some("1924");
+    day5: 5 => Some("5"), Some("12");
+    day6: 6 => Some("5934"), Some("26984457539");
+    day7: 7 => Some("37"), Some("168");
+    day8: 8 => Some("26"), Some("61229");
+    day9: 9 => Some("15"), Some("1134");
+    day10: 10 => Some("26397"), Some("288957");
+    day11: 11 => Some("1656"), Some("195");
+    day12: 12 => Some("10"), Some("36");
+    day13: 13 => Some("17"), Some("#####\n#...#\n#...#\n#...#\n#####\n.....\n.....");
+    day14: 14 => Some("1588"), Some("2188189693529");
+}
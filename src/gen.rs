@@ -0,0 +1,637 @@
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use crate::grid::Grid;
+
+/// A small seeded pseudo random number generator (SplitMix64), so every
+/// generated input can be reproduced from its seed
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// a number in `range`, both ends included
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = (*range.start(), *range.end());
+        match (end - start).checked_add(1) {
+            Some(span) => start + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// an index below `n`, panics if `n` is 0 as there is no index to pick
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "there is no index below 0");
+        self.range(0..=n as u64 - 1) as usize
+    }
+
+    /// true with a probability of `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// a generator writes a valid input of about the given size
+type Generator = fn(&mut Rng, usize) -> String;
+
+/// the days that have a generator, with the size used when none is given
+const GENERATORS: &[(usize, usize, Generator)] = &[
+    (1, 2000, day1),
+    (2, 1000, day2),
+    (3, 1000, day3),
+    (4, 100, day4),
+    (5, 500, day5),
+    (6, 300, day6),
+    (7, 1000, day7),
+    (8, 200, day8),
+    (9, 100, day9),
+    (10, 100, day10),
+    (11, 10, day11),
+    (12, 20, day12),
+    (13, 800, day13),
+    (14, 20, day14),
+    (15, 100, day15),
+    (16, 60, day16),
+];
+
+/// the size of the inputs generated for `day` when no size is given
+pub fn default_size(day: usize) -> Option<usize> {
+    GENERATORS
+        .iter()
+        .find(|(number, _, _)| *number == day)
+        .map(|(_, size, _)| *size)
+}
+
+/// generates an input for `day` from `seed`, `None` if the day has no generator
+///
+/// what `size` counts depends on the day, e.g. lines, bingo boards or grid columns
+pub fn generate(day: usize, seed: u64, size: usize) -> Option<String> {
+    let (_, _, generator) = GENERATORS.iter().find(|(number, _, _)| *number == day)?;
+    Some(generator(&mut Rng::new(seed), size.max(1)))
+}
+
+fn join(lines: impl Iterator<Item = String>, separator: &str) -> String {
+    lines.collect::<Vec<String>>().join(separator)
+}
+
+/// `size` depth measurements
+fn day1(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100..=200);
+    join(
+        (0..size).map(|_| {
+            depth = (depth + rng.range(0..=20)).saturating_sub(rng.range(0..=10));
+            depth.to_string()
+        }),
+        "\n",
+    )
+}
+
+/// `size` commands, the submarine never rises above the surface
+fn day2(rng: &mut Rng, size: usize) -> String {
+    let mut depth = 0;
+    join(
+        (0..size).map(|_| {
+            let amount = rng.range(1..=9);
+            match rng.below(3) {
+                0 if amount <= depth => {
+                    depth -= amount;
+                    format!("up {}", amount)
+                }
+                1 => {
+                    depth += amount;
+                    format!("down {}", amount)
+                }
+                _ => format!("forward {}", amount),
+            }
+        }),
+        "\n",
+    )
+}
+
+/// `size` distinct binary numbers of twelve bits (at most 4096), so a rating is
+/// always left after filtering
+fn day3(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<u64> = (0..=0xfff).collect();
+    rng.shuffle(&mut numbers);
+    join(
+        numbers.iter().take(size).map(|n| format!("{:012b}", n)),
+        "\n",
+    )
+}
+
+/// the draws and `size` boards, every number is drawn so every board wins
+fn day4(rng: &mut Rng, size: usize) -> String {
+    let mut draws: Vec<u64> = (0..100).collect();
+    rng.shuffle(&mut draws);
+    let mut sections = vec![join(draws.iter().map(u64::to_string), ",")];
+    for _ in 0..size {
+        let mut numbers: Vec<u64> = (0..100).collect();
+        rng.shuffle(&mut numbers);
+        let rows = numbers[..25]
+            .chunks(5)
+            .map(|row| join(row.iter().map(|n| format!("{:>2}", n)), " "));
+        sections.push(join(rows, "\n"));
+    }
+    sections.join("\n\n")
+}
+
+/// `size` horizontal, vertical and diagonal lines of vents
+fn day5(rng: &mut Rng, size: usize) -> String {
+    join(
+        (0..size).map(|_| {
+            let (x1, y1) = (rng.range(0..=999) as i64, rng.range(0..=999) as i64);
+            let length = rng.range(0..=300) as i64;
+            let (dx, dy) = match rng.below(3) {
+                0 => (*rng.pick(&[-1, 1]), 0),
+                1 => (0, *rng.pick(&[-1, 1])),
+                _ => (*rng.pick(&[-1, 1]), *rng.pick(&[-1, 1])),
+            };
+            // shorten the line so it stays on the map
+            let fits = |length: i64| {
+                (0..=999).contains(&(x1 + dx * length)) && (0..=999).contains(&(y1 + dy * length))
+            };
+            let length = (0..=length).rev().find(|&l| fits(l)).unwrap_or(0);
+            let (x2, y2) = (x1 + dx * length, y1 + dy * length);
+            format!("{},{} -> {},{}", x1, y1, x2, y2)
+        }),
+        "\n",
+    )
+}
+
+/// `size` lanternfish
+fn day6(rng: &mut Rng, size: usize) -> String {
+    join((0..size).map(|_| rng.range(1..=5).to_string()), ",")
+}
+
+/// `size` crabs
+fn day7(rng: &mut Rng, size: usize) -> String {
+    join((0..size).map(|_| rng.range(0..=1999).to_string()), ",")
+}
+
+/// `size` displays with randomly crossed wires
+fn day8(rng: &mut Rng, size: usize) -> String {
+    const DIGITS: [&str; 10] = [
+        "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+    ];
+    join(
+        (0..size).map(|_| {
+            let mut wires: Vec<char> = ('a'..='g').collect();
+            rng.shuffle(&mut wires);
+            let output: Vec<usize> = (0..4).map(|_| rng.below(10)).collect();
+            let mut scramble = |digit: &str| {
+                let mut segments: Vec<char> = digit
+                    .chars()
+                    .map(|segment| wires[(segment as u8 - b'a') as usize])
+                    .collect();
+                rng.shuffle(&mut segments);
+                segments.into_iter().collect::<String>()
+            };
+            let mut patterns: Vec<String> = DIGITS.iter().map(|digit| scramble(digit)).collect();
+            let output: Vec<String> = output
+                .iter()
+                .map(|&digit| scramble(DIGITS[digit]))
+                .collect();
+            rng.shuffle(&mut patterns);
+            format!("{} | {}", patterns.join(" "), output.join(" "))
+        }),
+        "\n",
+    )
+}
+
+/// a square of `rows` rows of digits in `digits`
+fn digit_rows(rng: &mut Rng, rows: usize, digits: RangeInclusive<u64>) -> String {
+    join(
+        (0..rows).map(|_| join((0..rows).map(|_| rng.range(digits.clone()).to_string()), "")),
+        "\n",
+    )
+}
+
+/// a height map with `size` rows and columns (at least five) with exactly
+/// four basins.
+///
+/// A row and a column of 9s split the map into four parts. Each part rises
+/// from a single low point, every other point has a neighbor at most as high
+/// that is closer to it, so no part has a second low point.
+fn day9(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5);
+    let ridge = (
+        rng.range(2..=size as u64 - 3) as usize,
+        rng.range(2..=size as u64 - 3) as usize,
+    );
+    let mut heights = Grid::new(size, size, 9);
+    let sides = |ridge: usize| [0..ridge, ridge + 1..size];
+    for xs in sides(ridge.0) {
+        for ys in sides(ridge.1) {
+            let low = (
+                xs.start + rng.below(xs.len()),
+                ys.start + rng.below(ys.len()),
+            );
+            let base = rng.range(0..=3) as usize;
+            for x in xs.clone() {
+                for y in ys.clone() {
+                    let distance = x.abs_diff(low.0) + y.abs_diff(low.1);
+                    // the bump keeps each point at least as high as its neighbor
+                    // one step closer to the low point
+                    let bump = match distance {
+                        0 => 0,
+                        _ => rng.below(2),
+                    };
+                    heights[(x, y)] = (base + distance + bump).min(8) as u8;
+                }
+            }
+        }
+    }
+    heights.to_string().trim_end().to_string()
+}
+
+/// `size` lines of chunks, about half of them corrupted and an odd number incomplete.
+///
+/// At most 20 chunks are open at once, so the completion scores fit in a `u64`.
+fn day10(rng: &mut Rng, size: usize) -> String {
+    const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+    let incomplete = (size / 2) | 1;
+    let mut lines: Vec<String> = (0..size.max(incomplete))
+        .map(|i| {
+            let mut line = String::new();
+            let mut open = Vec::new();
+            let length = rng.range(10..=100);
+            for _ in 0..length {
+                match open.pop() {
+                    Some(close) if open.len() >= 19 || rng.chance(0.4) => line.push(close),
+                    last => {
+                        open.extend(last);
+                        let (opening, closing) = *rng.pick(&PAIRS);
+                        line.push(opening);
+                        open.push(closing);
+                    }
+                }
+            }
+            if open.is_empty() {
+                let (opening, closing) = *rng.pick(&PAIRS);
+                line.push(opening);
+                open.push(closing);
+            }
+            if i >= incomplete {
+                // close the innermost chunk with the wrong character
+                let expected = open.last().copied();
+                let (_, wrong) = *rng.pick(&PAIRS);
+                let wrong = match Some(wrong) == expected {
+                    true => PAIRS.iter().map(|(_, c)| *c).find(|c| Some(*c) != expected),
+                    false => Some(wrong),
+                };
+                line.extend(wrong);
+            }
+            line
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// whether all octopuses flash at once within a thousand steps
+fn synchronizes(mut octopuses: Grid<u8>) -> bool {
+    for _ in 0..1000 {
+        let mut flashing = Vec::new();
+        for (pos, level) in octopuses.iter_mut() {
+            *level += 1;
+            if *level == 10 {
+                flashing.push(pos);
+            }
+        }
+        let mut flashes = 0;
+        while let Some(pos) = flashing.pop() {
+            flashes += 1;
+            for (pos, level) in octopuses.neighbors8_mut(pos) {
+                *level += 1;
+                if *level == 10 {
+                    flashing.push(pos);
+                }
+            }
+        }
+        if flashes == octopuses.width() * octopuses.height() {
+            return true;
+        }
+        for (_, level) in octopuses.iter_mut() {
+            if *level > 9 {
+                *level = 0;
+            }
+        }
+    }
+    false
+}
+
+/// energy levels of `size` rows and columns of octopuses that flash at once at some point.
+///
+/// Random levels often never synchronize, so grids that don't are redrawn with
+/// levels closer together; octopuses with equal levels always flash at once.
+fn day11(rng: &mut Rng, size: usize) -> String {
+    for spread in (0..=9).rev() {
+        for _ in 0..2 {
+            let low = rng.range(0..=9 - spread);
+            let mut octopuses = Grid::new(size, size, 0);
+            for (_, level) in octopuses.iter_mut() {
+                *level = rng.range(low..=low + spread) as u8;
+            }
+            if spread == 0 || synchronizes(octopuses.clone()) {
+                return octopuses.to_string().trim_end().to_string();
+            }
+        }
+    }
+    unreachable!("octopuses with equal levels synchronize")
+}
+
+/// `size` passages between small and big caves, no two big caves are connected
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let mut caves = vec!["start".to_string(), "end".to_string()];
+    let names: Vec<String> = ('a'..='z')
+        .flat_map(|a| ('a'..='z').map(move |b| format!("{}{}", a, b)))
+        .collect();
+    let mut names: Vec<&String> = names.iter().collect();
+    rng.shuffle(&mut names);
+    // there are only 676 names of two letters
+    let small = (2 + size / 4).min(500);
+    let big = (1 + size / 8).min(100);
+    caves.extend(names[..small].iter().map(|name| name.to_string()));
+    caves.extend(
+        names[small..small + big]
+            .iter()
+            .map(|name| name.to_uppercase()),
+    );
+    let is_big = |cave: &str| cave.starts_with(char::is_uppercase);
+    let mut passages = BTreeSet::new();
+    // the start and the end are always connected to some cave
+    passages.insert(("start".to_string(), caves[2].clone()));
+    passages.insert((caves[caves.len() - 1].clone(), "end".to_string()));
+    let mut attempts = 0;
+    while passages.len() < size.max(2) && attempts < 100 * size {
+        attempts += 1;
+        let (a, b) = (rng.pick(&caves).clone(), rng.pick(&caves).clone());
+        let duplicate = passages.contains(&(b.clone(), a.clone()));
+        if a != b && !(is_big(&a) && is_big(&b)) && !duplicate {
+            passages.insert((a, b));
+        }
+    }
+    let mut passages: Vec<String> = passages
+        .into_iter()
+        .map(|(a, b)| format!("{}-{}", a, b))
+        .collect();
+    rng.shuffle(&mut passages);
+    passages.join("\n")
+}
+
+/// `size` dots on a paper that is folded a few times.
+///
+/// The dots are placed on the folded paper and unfolded at random,
+/// so none of them ends up on a fold line.
+fn day13(rng: &mut Rng, size: usize) -> String {
+    let (folded_width, folded_height) = (rng.range(5..=40), rng.range(3..=8));
+    let (mut width, mut height) = (folded_width, folded_height);
+    let mut folds = Vec::new();
+    let mut axis_x = rng.chance(0.5);
+    for _ in 0..rng.range(2..=8) {
+        // every fold halves the paper, so unfolding doubles it
+        match axis_x {
+            true => {
+                folds.push(('x', width));
+                width = 2 * width + 1;
+            }
+            false => {
+                folds.push(('y', height));
+                height = 2 * height + 1;
+            }
+        }
+        axis_x = !axis_x;
+    }
+    let mut dots = BTreeSet::new();
+    for _ in 0..size {
+        let (mut x, mut y) = (
+            rng.range(0..=folded_width - 1),
+            rng.range(0..=folded_height - 1),
+        );
+        // unfold the paper in the order it was unfolded above
+        for (axis, position) in folds.iter() {
+            if rng.chance(0.5) {
+                match axis {
+                    'x' => x = 2 * position - x,
+                    _ => y = 2 * position - y,
+                }
+            }
+        }
+        dots.insert((x, y));
+    }
+    let mut dots: Vec<String> = dots
+        .into_iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect();
+    rng.shuffle(&mut dots);
+    // the fold that was added last has to be folded first
+    let folds = folds
+        .iter()
+        .rev()
+        .map(|(axis, position)| format!("fold along {}={}", axis, position));
+    format!("{}\n\n{}", dots.join("\n"), join(folds, "\n"))
+}
+
+/// a polymer template of `size` elements and rules for most pairs of elements,
+/// about every fifth pair has no rule and stays as it is
+fn day14(rng: &mut Rng, size: usize) -> String {
+    let mut elements: Vec<char> = ('A'..='Z').collect();
+    rng.shuffle(&mut elements);
+    elements.truncate(rng.range(3..=10) as usize);
+    let template: String = (0..size.max(2)).map(|_| *rng.pick(&elements)).collect();
+    let mut rules = Vec::new();
+    for a in elements.iter() {
+        for b in elements.iter() {
+            let element = *rng.pick(&elements);
+            if rng.chance(0.8) {
+                rules.push(format!("{}{} -> {}", a, b, element));
+            }
+        }
+    }
+    rng.shuffle(&mut rules);
+    format!("{}\n\n{}", template, rules.join("\n"))
+}
+
+/// risk levels of `size` rows and columns
+fn day15(rng: &mut Rng, size: usize) -> String {
+    digit_rows(rng, size, 1..=9)
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u64, length: usize) {
+    bits.extend((0..length).rev().map(|i| value >> i & 1 == 1));
+}
+
+/// appends a packet with at most `budget` packets in it
+fn packet(rng: &mut Rng, budget: &mut usize, bits: &mut Vec<bool>) {
+    push_bits(bits, rng.range(0..=7), 3);
+    *budget = budget.saturating_sub(1);
+    if *budget < 2 || rng.chance(0.3) {
+        push_bits(bits, 4, 3);
+        // groups of four bits, all but the last one start with a 1
+        let groups = rng.range(1..=4) as usize;
+        for group in (0..groups).rev() {
+            push_bits(bits, (group > 0) as u64, 1);
+            push_bits(bits, rng.range(0..=15), 4);
+        }
+        return;
+    }
+    // products only contain literals, so the values stay small
+    let type_id = *rng.pick(&[0, 1, 2, 3, 5, 6, 7]);
+    push_bits(bits, type_id, 3);
+    let count = match type_id {
+        5..=7 => 2,
+        _ => rng.range(1..=(*budget).min(4) as u64) as usize,
+    };
+    let mut packets = Vec::new();
+    for _ in 0..count {
+        match type_id {
+            1 => packet(rng, &mut 1, &mut packets),
+            _ => packet(rng, budget, &mut packets),
+        }
+    }
+    if packets.len() < 1 << 15 && rng.chance(0.5) {
+        push_bits(bits, 0, 1);
+        push_bits(bits, packets.len() as u64, 15);
+    } else {
+        push_bits(bits, 1, 1);
+        push_bits(bits, count as u64, 11);
+    }
+    bits.extend(packets);
+}
+
+/// a hexadecimal transmission of a packet containing about `size` packets
+fn day16(rng: &mut Rng, size: usize) -> String {
+    let mut bits = Vec::new();
+    let mut budget = size;
+    packet(rng, &mut budget, &mut bits);
+    while bits.len() % 4 != 0 {
+        bits.push(false);
+    }
+    bits.chunks(4)
+        .map(|nibble| {
+            let value = nibble.iter().fold(0, |value, &bit| value << 1 | bit as u32);
+            char::from_digit(value, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, Rng, GENERATORS};
+    use crate::{grid::Grid, registry};
+
+    #[test]
+    fn seeded() {
+        let mut rng = Rng::new(7);
+        let numbers: Vec<u64> = (0..100).map(|_| rng.range(3..=5)).collect();
+        assert!(numbers.iter().all(|n| (3..=5).contains(n)));
+        assert!((3..=5).all(|n| numbers.contains(&n)));
+        assert_eq!(rng.below(1), 0);
+        assert!(std::panic::catch_unwind(move || rng.below(0)).is_err());
+        for (day, size, _) in GENERATORS {
+            assert_eq!(generate(*day, 1, *size), generate(*day, 1, *size));
+            assert_ne!(generate(*day, 1, *size), generate(*day, 2, *size));
+        }
+        assert_eq!(generate(26, 1, 10), None);
+    }
+
+    /// the regions of points below 9, connected horizontally or vertically
+    fn basins(heights: &Grid<u8>) -> usize {
+        let mut seen = heights.map(|height| *height == 9);
+        let mut basins = 0;
+        for start in heights.positions() {
+            if seen[start] {
+                continue;
+            }
+            basins += 1;
+            seen[start] = true;
+            let mut todo = vec![start];
+            while let Some(pos) = todo.pop() {
+                for (next, _) in heights.neighbors4(pos) {
+                    if !seen[next] {
+                        seen[next] = true;
+                        todo.push(next);
+                    }
+                }
+            }
+        }
+        basins
+    }
+
+    #[test]
+    fn height_maps_have_four_basins() {
+        let registry = registry();
+        let day = registry.get(9).unwrap();
+        for seed in 0..200 {
+            for size in [1, 5, 12] {
+                let input = generate(9, seed, size).unwrap();
+                let rows = input
+                    .lines()
+                    .map(|row| row.bytes().map(|b| b - b'0').collect());
+                let heights = Grid::from_rows(rows.collect()).unwrap();
+                assert_eq!(
+                    basins(&heights),
+                    4,
+                    "seed {}, size {}\n{}",
+                    seed,
+                    size,
+                    input
+                );
+                // and every basin flows to a low point of its own
+                let low_points = heights
+                    .iter()
+                    .filter(|(pos, height)| heights.neighbors4(*pos).all(|(_, h)| h > height));
+                assert_eq!(low_points.count(), 4, "seed {}, size {}", seed, size);
+                let solution = day.parse(&input).unwrap();
+                if let Err(err) = day.solve(solution.as_ref(), 2) {
+                    panic!("seed {}, size {}: {}\n{}", seed, size, err, input);
+                }
+            }
+        }
+    }
+
+    /// every generated input is accepted and solved without errors, the unfinished
+    /// days 15 and 16 are only parsed
+    #[test]
+    fn generated_inputs_are_valid() {
+        let registry = registry();
+        for seed in 0..5 {
+            for day in 1..=16 {
+                let input = generate(day, seed, 20).unwrap();
+                let solution = registry.get(day).unwrap().parse(&input);
+                let solution = solution.unwrap_or_else(|err| panic!("{}\n{}", err, input));
+                if day > 14 {
+                    continue;
+                }
+                for part in [1, 2] {
+                    let output = registry.get(day).unwrap().solve(solution.as_ref(), part);
+                    if let Err(err) = output {
+                        panic!("seed {}, part {}: {}\n{}", seed, part, err, input);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    fmt::{self, Display},
+    mem,
+    ops::{Index, IndexMut},
+};
+
+/// A position on a grid, `(x, y)` with `(0, 0)` in the top left corner
+pub type Pos = (usize, usize);
+
+// the offsets are in row-major order, `NeighborsMut` relies on it
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cell: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![cell; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// a grid from its rows, or the index of the first row that is not as long
+    /// as the ones before it
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, usize> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().position(|row| row.len() != width) {
+            return Err(row);
+        }
+        Ok(Grid {
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+            width,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos).then(|| pos.1 * self.width + pos.0)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// all positions, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// all cells with their positions, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut T)> {
+        self.positions().zip(self.cells.iter_mut())
+    }
+
+    /// a grid of the same size with `f` applied to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// the cells of column `x` from top to bottom, empty if there is no such column
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    fn neighbors(
+        &self,
+        pos: Pos,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (Pos, &T)> {
+        offsets.iter().filter_map(move |&offset| {
+            let pos = self.offset(pos, offset)?;
+            Some((pos, &self[pos]))
+        })
+    }
+
+    /// the cells above, left, right and below of `pos`
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        self.neighbors(pos, &ORTHOGONAL)
+    }
+
+    /// the cells around `pos`, including the diagonal ones
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        self.neighbors(pos, &ALL_AROUND)
+    }
+
+    pub fn neighbors4_mut(&mut self, pos: Pos) -> NeighborsMut<'_, T> {
+        NeighborsMut::new(self, pos, &ORTHOGONAL)
+    }
+
+    pub fn neighbors8_mut(&mut self, pos: Pos) -> NeighborsMut<'_, T> {
+        NeighborsMut::new(self, pos, &ALL_AROUND)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!(
+                "{:?} is outside of the {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside of the {}x{} grid", pos, width, height),
+        }
+    }
+}
+
+/// one line per row, without separators between the cells
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Mutable references to the neighbors of a cell.
+///
+/// The neighbors are visited in row-major order, so every one of them is
+/// split off the cells following the previous one.
+pub struct NeighborsMut<'a, T> {
+    /// the cells after the last returned neighbor
+    rest: &'a mut [T],
+    /// the index of the first cell of `rest`
+    start: usize,
+    width: usize,
+    height: usize,
+    pos: Pos,
+    offsets: std::slice::Iter<'static, (isize, isize)>,
+}
+
+impl<'a, T> NeighborsMut<'a, T> {
+    fn new(grid: &'a mut Grid<T>, pos: Pos, offsets: &'static [(isize, isize)]) -> Self {
+        NeighborsMut {
+            rest: &mut grid.cells,
+            start: 0,
+            width: grid.width,
+            height: grid.height,
+            pos,
+            offsets: offsets.iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for NeighborsMut<'a, T> {
+    type Item = (Pos, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, y) = self.pos;
+        let pos = self.offsets.find_map(|&(dx, dy)| {
+            let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            (pos.0 < self.width && pos.1 < self.height).then_some(pos)
+        })?;
+        let index = pos.1 * self.width + pos.0;
+        let rest = mem::take(&mut self.rest);
+        let (cell, rest) = rest[index - self.start..].split_first_mut()?;
+        self.rest = rest;
+        self.start = index + 1;
+        Some((pos, cell))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    fn digits(rows: &[&str]) -> Grid<u8> {
+        let rows = rows
+            .iter()
+            .map(|row| row.bytes().map(|b| b - b'0').collect());
+        Grid::from_rows(rows.collect()).unwrap()
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits(&["123", "456"]);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((2, 1)), Some(&6));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        let rows: Vec<&[u8]> = grid.rows().collect();
+        assert_eq!(rows, [[1, 2, 3], [4, 5, 6]]);
+        let column: Vec<&u8> = grid.column(1).collect();
+        assert_eq!(column, [&2, &5]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.map(|digit| digit % 2 == 0).get((1, 0)), Some(&true));
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), Err(1));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = digits(&["123", "456", "789"]);
+        let values = |neighbors: Vec<(_, &u8)>| -> Vec<u8> {
+            neighbors.into_iter().map(|(_, &value)| value).collect()
+        };
+        assert_eq!(values(grid.neighbors4((0, 0)).collect()), [2, 4]);
+        assert_eq!(values(grid.neighbors4((1, 1)).collect()), [2, 4, 6, 8]);
+        assert_eq!(values(grid.neighbors8((0, 0)).collect()), [2, 4, 5]);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors8((2, 2)).next(), Some(((1, 1), &5)));
+    }
+
+    #[test]
+    fn mutable_neighbors() {
+        let mut grid = Grid::new(3, 3, 0);
+        for (_, cell) in grid.neighbors8_mut((1, 1)) {
+            *cell += 1;
+        }
+        for (pos, cell) in grid.neighbors4_mut((2, 2)) {
+            assert!(pos == (2, 1) || pos == (1, 2));
+            *cell += 1;
+        }
+        assert_eq!(grid.to_string(), "111\n102\n121\n");
+        grid[(1, 1)] = 9;
+        assert_eq!(
+            grid.neighbors8_mut((0, 0)).map(|(_, cell)| *cell).max(),
+            Some(9)
+        );
+    }
+}
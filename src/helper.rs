@@ -1,47 +1,240 @@
 use colored::{self, Colorize};
-use std::{collections::HashMap, fmt, fs};
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
 
-pub fn load_input_for_day(day: usize) -> String {
-    // load input
-    fs::read_to_string(format!("inputs/day{}.txt", day)).expect("input file missing!")
+#[derive(Debug)]
+pub enum InputError {
+    Missing { day: usize, path: PathBuf },
+    NoExample { day: usize },
+    Unreadable { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { day, path } => {
+                write!(f, "input for day {} missing: {}", day, path.display())
+            }
+            InputError::NoExample { day } => write!(f, "there is no example for day {}", day),
+            InputError::Unreadable { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Missing { .. } | InputError::NoExample { .. } => None,
+            InputError::Unreadable { source, .. } => Some(source),
+        }
+    }
+}
+
+pub fn input_path(input_dir: &Path, day: usize) -> PathBuf {
+    input_dir.join(format!("day{}.txt", day))
+}
+
+pub fn load_input(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError::Unreadable {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// loads `dayN.txt` from the input directory
+pub fn load_input_for_day(input_dir: &Path, day: usize) -> Result<String, InputError> {
+    let path = input_path(input_dir, day);
+    if !path.is_file() {
+        return Err(InputError::Missing { day, path });
+    }
+    load_input(&path)
+}
+
+/// Removes the differences editors and operating systems introduce into inputs:
+/// line breaks become `\n`, trailing whitespace is removed from every line and
+/// blank lines at the start and the end are dropped, as is the final line break.
+pub fn normalize(input: &str) -> String {
+    let lines: Vec<&str> = input.lines().map(str::trim_end).collect();
+    let start = lines
+        .iter()
+        .position(|line| !line.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end].join("\n")
+}
+
+/// A value of an output field.
+///
+/// Keeps track of the type of the value for the machine-readable renderers,
+/// anything convertible into it can be passed to `OutputFormatter::var`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Str(String),
+    /// rows of characters, like the folded paper of day 13
+    Grid(Vec<String>),
+    List(Vec<Answer>),
+    /// a missing value, converted from `Option::None`
+    None,
+}
+
+impl Answer {
+    /// a grid with one row per line of `text`
+    pub fn grid(text: &str) -> Answer {
+        Answer::Grid(text.trim_end().lines().map(str::to_string).collect())
+    }
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(rows) if rows.len() > 1)
+    }
+}
+
+macro_rules! integer_answers {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for Answer {
+            fn from(value: $ty) -> Self {
+                Answer::Integer(value as i128)
+            }
+        }
+    )*};
+}
+integer_answers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Str(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Str(value.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Answer::None, Into::into)
+    }
+}
+
+impl<T: Into<Answer>> From<Vec<T>> for Answer {
+    fn from(values: Vec<T>) -> Self {
+        Answer::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Answer>, const N: usize> From<[T; N]> for Answer {
+    fn from(values: [T; N]) -> Self {
+        Answer::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Str(value) => write!(f, "{}", value),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::List(values) => {
+                let values: Vec<String> = values.iter().map(Answer::to_string).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+            Answer::None => write!(f, "none"),
+        }
+    }
 }
 
 pub struct OutputFormatter {
     task: usize,
-    fields: HashMap<String, Box<dyn fmt::Debug>>,
+    /// the fields in the order they were added
+    fields: Vec<(String, Answer)>,
+    /// name of the field holding the answer to the task
+    answer: Option<String>,
 }
 impl OutputFormatter {
     pub fn new(task: usize) -> OutputFormatter {
         OutputFormatter {
             task,
-            fields: HashMap::new(),
+            fields: Vec::new(),
+            answer: None,
         }
     }
-    pub fn var<T: 'static + fmt::Debug>(&mut self, name: &str, value: T) -> &mut Self {
-        self.fields.insert(name.to_string(), Box::new(value));
+    /// adds a field, replacing the value of an existing field with the same name
+    pub fn var(mut self, name: &str, value: impl Into<Answer>) -> Self {
+        let value = value.into();
+        match self.fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, old)) => *old = value,
+            None => self.fields.push((name.to_string(), value)),
+        }
         self
     }
+    /// like `var`, but marks the value as the answer to the task
+    pub fn answer(mut self, name: &str, value: impl Into<Answer>) -> Self {
+        self.answer = Some(name.to_string());
+        self.var(name, value)
+    }
+    pub fn task(&self) -> usize {
+        self.task
+    }
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &Answer)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+    pub fn get(&self, name: &str) -> Option<&Answer> {
+        self.fields()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value)
+    }
+    pub fn is_answer(&self, name: &str) -> bool {
+        self.answer.as_deref() == Some(name)
+    }
+    /// the value marked as answer, formatted the same way as it is printed
+    pub fn answer_value(&self) -> Option<String> {
+        self.get(self.answer.as_ref()?).map(Answer::to_string)
+    }
     pub fn print(&self) {
         let task = format!("(Task {} ): ", self.task.to_string().bold()).yellow();
         print!("{}", task);
-        let mut field_iter = self.fields.iter();
-        if self.fields.len() == 1 {
-            if let Some((k, v)) = field_iter.next() {
-                println!(" {}={}", k.green(), format!("{:?}", v).blue());
+        if let [(k, v)] = self.fields.as_slice() {
+            match v.is_multiline() {
+                true => println!(" {}=", k.green()),
+                false => println!(" {}={}", k.green(), v.to_string().blue()),
             }
+            print_block(v, "\t");
             return;
-        } else {
-            println!();
         }
+        println!();
         let longest_field = self
             .fields
-            .keys()
-            .max_by_key(|field| field.len())
-            .unwrap()
-            .len();
-        for (k, v) in field_iter {
+            .iter()
+            .map(|(field, _)| field.len())
+            .max()
+            .unwrap_or(0);
+        for (k, v) in self.fields.iter() {
             let padding = " ".repeat(longest_field - k.len());
-            println!("\t↳{}{} ={}", k.green(), padding, format!("{:?}", v).blue());
+            match v.is_multiline() {
+                true => println!("\t↳{}{} =", k.green(), padding),
+                false => println!("\t↳{}{} ={}", k.green(), padding, v.to_string().blue()),
+            }
+            print_block(v, "\t  ");
+        }
+    }
+}
+
+/// prints multi-line values on their own lines below the field name
+fn print_block(value: &Answer, indent: &str) {
+    if let (true, Answer::Grid(rows)) = (value.is_multiline(), value) {
+        for row in rows {
+            println!("{}{}", indent, row.blue());
         }
     }
 }
@@ -56,4 +249,36 @@ mod tests {
         use super::out;
         out(1).var("sum", 2345).var("funny", [12, 324, 523]).print()
     }
+
+    #[test]
+    fn normalize_input() {
+        use super::normalize;
+        assert_eq!(normalize("1\r\n2 \r\n\r\n3\t\n\n"), "1\n2\n\n3");
+        assert_eq!(normalize("\n \n 8  2\n 3  4"), " 8  2\n 3  4");
+        assert_eq!(normalize(" \n\n"), "");
+    }
+
+    #[test]
+    fn fields_keep_their_order() {
+        use super::{out, Answer};
+        let output = out(1)
+            .var("gamma", 22)
+            .var("epsilon", 9)
+            .answer("product", 198)
+            .var("gamma", 23);
+        let names: Vec<&str> = output.fields().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["gamma", "epsilon", "product"]);
+        assert_eq!(output.get("gamma"), Some(&Answer::Integer(23)));
+        assert_eq!(output.answer_value(), Some("198".into()));
+    }
+
+    #[test]
+    fn answer_conversions() {
+        use super::Answer;
+        assert_eq!(Answer::from(Some(195usize)).to_string(), "195");
+        assert_eq!(Answer::from(None::<u32>), Answer::None);
+        assert_eq!(Answer::from("text").to_string(), "text");
+        assert_eq!(Answer::from([1905, 907]).to_string(), "[1905, 907]");
+        assert_eq!(Answer::grid("#.\n.#\n").to_string(), "#.\n.#");
+    }
 }
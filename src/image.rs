@@ -0,0 +1,417 @@
+use std::io::{self, Write};
+
+/// A color as red, green and blue
+pub type Rgb = [u8; 3];
+
+/// An image whose pixels are indices into a list of colors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// the rows from top to bottom
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: u8) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: u8) {
+        for row in y..y + height {
+            let start = row * self.width + x;
+            self.pixels[start..start + width].fill(color);
+        }
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string())
+}
+
+/// writes a binary PPM (P6), the simplest format most image viewers can open
+pub fn write_ppm(image: &Image, colors: &[Rgb], out: &mut impl Write) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.width, image.height)?;
+    let pixels: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|&pixel| colors[pixel as usize])
+        .collect();
+    out.write_all(&pixels)
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = match c & 1 {
+                1 => 0xedb88320 ^ (c >> 1),
+                _ => c >> 1,
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let crc = bytes.iter().fold(0xffffffff, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    });
+    crc ^ 0xffffffff
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        (a, b) = (a % 65521, b % 65521);
+    }
+    (b << 16) | a
+}
+
+/// Packs values into bytes, starting at the least significant bit
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= value << self.bits;
+        self.bits += bits;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// huffman codes are stored starting at their most significant bit
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const MAX_MATCH: usize = 258;
+const MAX_DISTANCE: usize = 32768;
+
+/// writes a literal or length with the fixed huffman codes of deflate
+fn write_symbol(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+/// the code of a length or distance is the last base not above it,
+/// the difference to the base follows in extra bits
+fn base_code(bases: &[u16], value: usize) -> usize {
+    bases
+        .iter()
+        .rposition(|&base| base as usize <= value)
+        .unwrap()
+}
+
+/// compresses `data` into a single deflate block with fixed codes, only
+/// repetitions at the given `distances` are found, which is enough for
+/// images made of flat cells
+fn deflate(data: &[u8], distances: &[usize]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    // the final block, compressed with the fixed codes
+    writer.write(1, 1);
+    writer.write(1, 2);
+    let mut pos = 0;
+    while pos < data.len() {
+        let longest = distances
+            .iter()
+            .filter(|&&distance| distance <= pos && distance <= MAX_DISTANCE)
+            .map(|&distance| {
+                let max = MAX_MATCH.min(data.len() - pos);
+                let same = (0..max).take_while(|&i| data[pos + i] == data[pos + i - distance]);
+                (same.count(), distance)
+            })
+            .max();
+        match longest {
+            Some((length, distance)) if length >= 3 => {
+                let code = base_code(&LENGTH_BASES, length);
+                write_symbol(&mut writer, 257 + code as u32);
+                let extra = length - LENGTH_BASES[code] as usize;
+                writer.write(extra as u32, LENGTH_EXTRA_BITS[code]);
+                let code = base_code(&DISTANCE_BASES, distance);
+                writer.write_code(code as u32, 5);
+                let extra = distance - DISTANCE_BASES[code] as usize;
+                writer.write(extra as u32, DISTANCE_EXTRA_BITS[code]);
+                pos += length;
+            }
+            _ => {
+                write_symbol(&mut writer, data[pos] as u32);
+                pos += 1;
+            }
+        }
+    }
+    write_symbol(&mut writer, 256);
+    writer.finish()
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    let mut chunk = kind.to_vec();
+    chunk.extend_from_slice(data);
+    out.write_all(&chunk)?;
+    out.write_all(&crc32(&chunk).to_be_bytes())
+}
+
+/// writes a PNG with a palette of up to 256 colors
+pub fn write_png(image: &Image, colors: &[Rgb], out: &mut impl Write) -> io::Result<()> {
+    if colors.len() > 256 {
+        return Err(invalid("a PNG palette has at most 256 colors"));
+    }
+    out.write_all(b"\x89PNG\r\n\x1a\n")?;
+    let mut header = Vec::new();
+    header.extend_from_slice(&(image.width as u32).to_be_bytes());
+    header.extend_from_slice(&(image.height as u32).to_be_bytes());
+    // 8 bits per pixel, indexed colors, no interlacing
+    header.extend_from_slice(&[8, 3, 0, 0, 0]);
+    write_chunk(out, b"IHDR", &header)?;
+    write_chunk(out, b"PLTE", colors.as_flattened())?;
+    // every row starts with filter type 0, the row is stored as is
+    let mut rows = Vec::with_capacity((image.width + 1) * image.height);
+    for row in image.pixels.chunks(image.width.max(1)) {
+        rows.push(0);
+        rows.extend_from_slice(row);
+    }
+    let mut data = vec![0x78, 0x01];
+    data.extend(deflate(&rows, &[1, image.width + 1]));
+    data.extend_from_slice(&adler32(&rows).to_be_bytes());
+    write_chunk(out, b"IDAT", &data)?;
+    write_chunk(out, b"IEND", &[])
+}
+
+const MAX_LZW_CODE: usize = 4096;
+
+/// compresses the pixels with the variable length LZW of GIF, the pixels
+/// have to be below `1 << min_code_size`
+fn lzw(pixels: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1 << min_code_size;
+    let end = clear + 1;
+    // the code of a prefix followed by a pixel, 0 if it has none yet
+    let mut codes = vec![0u16; MAX_LZW_CODE << min_code_size];
+    let mut writer = BitWriter::default();
+    let mut code_size = min_code_size + 1;
+    let mut next = end + 1;
+    writer.write(clear, code_size);
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.write(end, code_size);
+        return writer.finish();
+    };
+    let mut prefix = first as u32;
+    for &pixel in rest {
+        let slot = ((prefix as usize) << min_code_size) | pixel as usize;
+        if codes[slot] != 0 {
+            prefix = codes[slot] as u32;
+            continue;
+        }
+        writer.write(prefix, code_size);
+        codes[slot] = next as u16;
+        next += 1;
+        // the decoder adds its codes one step later, so it widens them one step later
+        if next > 1 << code_size && code_size < 12 {
+            code_size += 1;
+        }
+        if next as usize == MAX_LZW_CODE {
+            writer.write(clear, code_size);
+            codes.fill(0);
+            code_size = min_code_size + 1;
+            next = end + 1;
+        }
+        prefix = pixel as u32;
+    }
+    writer.write(prefix, code_size);
+    // the decoder adds a code for the last prefix before reading the end
+    if next == 1 << code_size && code_size < 12 {
+        code_size += 1;
+    }
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+/// Writes an animated GIF frame by frame, looping forever
+pub struct Gif<W: Write> {
+    out: W,
+    width: usize,
+    height: usize,
+    min_code_size: u32,
+}
+
+impl<W: Write> Gif<W> {
+    /// writes the header, all frames share the size and the up to 256 colors
+    pub fn new(mut out: W, width: usize, height: usize, colors: &[Rgb]) -> io::Result<Gif<W>> {
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(invalid("a GIF is at most 65535 pixels wide and high"));
+        }
+        if colors.len() > 256 {
+            return Err(invalid("a GIF has at most 256 colors"));
+        }
+        // the color table has a power of two of at least 4 entries
+        let bits = colors.len().next_power_of_two().trailing_zeros().max(2);
+        out.write_all(b"GIF89a")?;
+        out.write_all(&(width as u16).to_le_bytes())?;
+        out.write_all(&(height as u16).to_le_bytes())?;
+        out.write_all(&[0xf0 | (bits as u8 - 1), 0, 0])?;
+        let mut table = colors.as_flattened().to_vec();
+        table.resize(3 << bits, 0);
+        out.write_all(&table)?;
+        out.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+        Ok(Gif {
+            out,
+            width,
+            height,
+            min_code_size: bits,
+        })
+    }
+
+    /// adds a frame shown for `delay` hundredths of a second
+    pub fn frame(&mut self, image: &Image, delay: u16) -> io::Result<()> {
+        if (image.width, image.height) != (self.width, self.height) {
+            return Err(invalid("all frames of a GIF have the same size"));
+        }
+        self.out.write_all(&[0x21, 0xf9, 4, 0])?;
+        self.out.write_all(&delay.to_le_bytes())?;
+        self.out.write_all(&[0, 0, 0x2c, 0, 0, 0, 0])?;
+        self.out.write_all(&(self.width as u16).to_le_bytes())?;
+        self.out.write_all(&(self.height as u16).to_le_bytes())?;
+        self.out.write_all(&[0, self.min_code_size as u8])?;
+        for block in lzw(&image.pixels, self.min_code_size).chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0])
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(b"\x3b")?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adler32, crc32, lzw, write_png, write_ppm, Gif, Image};
+
+    /// a plain GIF decoder, reading codes until the end code
+    fn unlzw(bytes: &[u8], min_code_size: u32) -> Vec<u8> {
+        let (clear, end) = (1 << min_code_size, (1 << min_code_size) + 1);
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let (mut code_size, mut pos, mut previous) = (min_code_size + 1, 0, None::<usize>);
+        let mut pixels = Vec::new();
+        loop {
+            let bits =
+                (0..code_size as usize).map(|i| (bytes[(pos + i) / 8] >> ((pos + i) % 8)) & 1);
+            let code = bits.rev().fold(0, |code, bit| (code << 1) | bit as usize);
+            pos += code_size as usize;
+            if code == clear {
+                table = (0..clear).map(|pixel| vec![pixel as u8]).collect();
+                table.extend([vec![], vec![]]);
+                (code_size, previous) = (min_code_size + 1, None);
+                continue;
+            }
+            if code == end {
+                return pixels;
+            }
+            let entry = match (table.get(code), previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => {
+                    let mut entry = table[previous].clone();
+                    entry.push(table[previous][0]);
+                    entry
+                }
+                (None, None) => panic!("unknown first code {}", code),
+            };
+            if let Some(previous) = previous {
+                let mut added = table[previous].clone();
+                added.push(entry[0]);
+                table.push(added);
+                if table.len() == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            pixels.extend_from_slice(&entry);
+            previous = Some(code);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let noise: Vec<u8> = (0..20000u32).map(|i| (i * i / 7 % 31) as u8).collect();
+        for pixels in [vec![], vec![3], vec![1; 1000], noise] {
+            assert_eq!(unlzw(&lzw(&pixels, 5), 5), pixels);
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn formats() {
+        let mut image = Image::new(3, 2, 0);
+        image.fill(1, 0, 2, 2, 1);
+        let colors = [[0, 0, 0], [255, 128, 0]];
+        let mut ppm = Vec::new();
+        write_ppm(&image, &colors, &mut ppm).unwrap();
+        assert_eq!(&ppm[..11], b"P6\n3 2\n255\n");
+        assert_eq!(&ppm[11..17], [0, 0, 0, 255, 128, 0]);
+        let mut png = Vec::new();
+        write_png(&image, &colors, &mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+        let mut gif = Vec::new();
+        let mut encoder = Gif::new(&mut gif, 3, 2, &colors).unwrap();
+        encoder.frame(&image, 10).unwrap();
+        assert!(encoder.frame(&Image::new(1, 1, 0), 10).is_err());
+        encoder.finish().unwrap();
+        assert!(gif.starts_with(b"GIF89a\x03\x00\x02\x00"));
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
@@ -1,14 +1,27 @@
-use colored::Colorize;
+#![allow(non_snake_case)]
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
 
+use colored::{ColoredString, Colorize};
+
+pub mod answers;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod dashboard;
 pub mod day1;
 pub mod day10;
 pub mod day11;
 pub mod day12;
 pub mod day13;
 pub mod day14;
-// pub mod day15; comment out until day 16 is completed
+pub mod day15;
 pub mod day16;
-// pub mod day15;
 pub mod day2;
 pub mod day3;
 pub mod day4;
@@ -17,55 +30,562 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
-mod helper;
+pub mod error;
+pub mod export;
+pub mod fixtures;
+pub mod gen;
+pub mod grid;
+pub mod helper;
+pub mod image;
+pub mod parse;
+pub mod pool;
+pub mod reference;
+pub mod render;
+pub mod scaffold;
+pub mod server;
+pub mod solution;
+pub mod terminal;
+pub mod timing;
+pub mod visualize;
+pub mod watch;
 
-const HEADER_WIDTH: usize = 70;
+use answers::{answers_path, Answers, Verdict};
+use cli::{InputSource, RunOptions};
+use client::{submit_answer, Client, Outcome};
+use config::Config;
+use error::AocError;
+use helper::{input_path, load_input, load_input_for_day, normalize, InputError, OutputFormatter};
+use render::Format;
+use solution::{Day, Registry, Solution};
+use timing::{measure, Samples, Timing};
+use watch::Watcher;
 
-fn sep(day: usize) {
+/// prints the header of a day, spanning `width` columns
+fn sep(day: usize, width: usize) {
     let day = format!("( Day {} )", day);
-    let remaining = HEADER_WIDTH - day.len();
-    println!("");
-    println!("{}", "-".repeat(HEADER_WIDTH));
+    let remaining = width.saturating_sub(day.len());
+    println!();
+    println!("{}", "-".repeat(width));
     println!(
         "{}{}{}",
         " ".repeat(remaining / 2),
         day.yellow().bold(),
         " ".repeat(remaining - remaining / 2)
     );
-    println!("{}", "-".repeat(HEADER_WIDTH));
-}
-
-pub fn run() {
-    sep(1);
-    day1::run();
-    sep(2);
-    day2::run();
-    sep(3);
-    day3::run();
-    sep(4);
-    day4::run();
-    sep(5);
-    day5::run();
-    sep(6);
-    day6::run();
-    sep(7);
-    day7::run();
-    sep(8);
-    day8::run();
-    sep(9);
-    day9::run();
-    sep(10);
-    day10::run();
-    sep(11);
-    day11::run();
-    sep(12);
-    day12::run();
-    sep(13);
-    day13::run();
-    sep(14);
-    day14::run();
-    sep(15);
-    // day15::run();
-    sep(16);
-    day16::run();
+    println!("{}", "-".repeat(width));
+}
+
+/// All the days that have a solution.
+///
+/// Day 15 and 16 are not finished yet, the runner reports their
+/// unfinished parts as panicked.
+pub fn registry() -> Registry {
+    Registry::new()
+        .register::<day1::Day1>(1)
+        .register::<day2::Day2>(2)
+        .register::<day3::Day3>(3)
+        .register::<day4::Day4>(4)
+        .register::<day5::Day5>(5)
+        .register::<day6::Day6>(6)
+        .register::<day7::Day7>(7)
+        .register::<day8::Day8>(8)
+        .register::<day9::Day9>(9)
+        .register::<day10::Day10>(10)
+        .register::<day11::Day11>(11)
+        .register::<day12::Day12>(12)
+        .register::<day13::Day13>(13)
+        .register::<day14::Day14>(14)
+        .register::<day15::Day15>(15)
+        .register::<day16::Day16>(16)
+}
+
+/// loads the input of a day and normalizes it
+fn read_input(day: usize, source: &InputSource, input_dir: &Path) -> Result<String, InputError> {
+    let input = match source {
+        InputSource::Default => load_input_for_day(input_dir, day)?,
+        InputSource::File(path) => load_input(path)?,
+        InputSource::Example => fixtures::load_example(day).ok_or(InputError::NoExample { day })?,
+        InputSource::Stdin => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|source| InputError::Unreadable {
+                    path: "<stdin>".into(),
+                    source,
+                })?;
+            input
+        }
+    };
+    Ok(normalize(&input))
+}
+
+/// prints a message for humans, on stderr when stdout is machine-readable
+fn notice(format: Format, message: ColoredString) {
+    match format {
+        Format::Text => println!("{}", message),
+        _ => eprintln!("{}", message),
+    }
+}
+
+/// the file the input of a day is read from, `None` for stdin
+fn input_file(day: usize, source: &InputSource, input_dir: &Path) -> Option<PathBuf> {
+    match source {
+        InputSource::Default => Some(input_path(input_dir, day)),
+        InputSource::File(path) => Some(path.clone()),
+        InputSource::Example => Some(fixtures::example_path(day)),
+        InputSource::Stdin => None,
+    }
+}
+
+fn print_error(format: Format, err: &AocError) {
+    notice(format, err.to_string().red());
+}
+
+fn print_time(step: &str, samples: &Samples) {
+    println!("\t{}", format!("⏱ {}: {}", step, samples).dimmed());
+}
+
+/// prints how many answers were correct, wrong and unknown
+fn print_summary(verdicts: &[Verdict]) {
+    let count = |wanted: fn(&Verdict) -> bool| verdicts.iter().filter(|v| wanted(v)).count();
+    println!();
+    println!(
+        "{}, {}, {}",
+        format!("{} correct", count(|v| *v == Verdict::Correct)).green(),
+        format!("{} wrong", count(|v| matches!(v, Verdict::Wrong { .. }))).red(),
+        format!("{} unknown", count(|v| *v == Verdict::Unknown)).yellow()
+    );
+}
+
+/// compares an answer to the one from the puzzle description for the examples and
+/// to the known answers for everything else, `None` if there is nothing to compare to
+fn verdict(
+    source: &InputSource,
+    answers: Option<&Answers>,
+    day: usize,
+    part: u8,
+    answer: Option<&str>,
+) -> Option<Verdict> {
+    match (source, answers) {
+        (InputSource::Example, _) => Some(Verdict::of(
+            fixtures::fixture(day).and_then(|f| f.expected(part)),
+            answer,
+        )),
+        (_, Some(answers)) => Some(answers.check(day, part, answer)),
+        _ => None,
+    }
+}
+
+/// The result of a single part
+pub struct PartReport {
+    pub part: u8,
+    pub output: Result<OutputFormatter, AocError>,
+    pub samples: Samples,
+}
+
+/// everything computed for a day, kept until it is its turn to be printed
+struct DayReport {
+    day: usize,
+    /// the duration of parsing and the parts
+    result: Result<(Samples, Vec<PartReport>), AocError>,
+}
+
+/// parses the input of a day and solves the selected parts,
+/// in parallel if more than one job is allowed
+fn solve_input(
+    day: &Day,
+    options: &RunOptions,
+    input: &str,
+) -> Result<(Samples, Vec<PartReport>), AocError> {
+    let runs = options.timing.runs();
+    let (solution, parse_samples) = measure(runs, || day.parse(input));
+    let solution = solution?;
+    let solve = |part| {
+        let (output, samples) = measure(runs, || day.solve(solution.as_ref(), part));
+        PartReport {
+            part,
+            output,
+            samples,
+        }
+    };
+    let parts: Vec<u8> = [1, 2]
+        .into_iter()
+        .filter(|part| options.runs_part(*part))
+        .collect();
+    let mut reports = Vec::new();
+    pool::ordered(
+        &parts,
+        options.jobs,
+        |part| solve(*part),
+        |report| reports.push(report),
+    );
+    Ok((parse_samples, reports))
+}
+
+/// A day being solved on its own thread
+pub struct Solving {
+    result: Receiver<Result<(Samples, Vec<PartReport>), AocError>>,
+    thread: JoinHandle<()>,
+}
+
+impl Solving {
+    /// starts to parse `input` and solve the parts selected by `options`
+    pub fn start(day: &Day, options: &RunOptions, input: String) -> Solving {
+        let (sender, result) = mpsc::channel();
+        let (day, options) = (*day, options.clone());
+        let thread = thread::spawn(move || {
+            let _ = sender.send(solve_input(&day, &options, &input));
+        });
+        Solving { result, thread }
+    }
+
+    /// waits for the parse time and the parts, at most for `limit`
+    pub fn wait(&self, limit: Option<Duration>) -> Result<(Samples, Vec<PartReport>), AocError> {
+        let result = match limit {
+            Some(limit) => self.result.recv_timeout(limit),
+            None => self.result.recv().map_err(RecvTimeoutError::from),
+        };
+        match result {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(AocError::Timeout {
+                day: None,
+                limit: limit.unwrap_or_default(),
+            }),
+            Err(RecvTimeoutError::Disconnected) => Err(AocError::Panic {
+                day: None,
+                message: "the solution stopped without a result".into(),
+            }),
+        }
+    }
+
+    /// waits until the thread is done, even after `wait` timed out, as a
+    /// thread can not be stopped
+    pub fn finish(self) {
+        let _ = self.thread.join();
+    }
+}
+
+/// solves `input` on its own thread, which is abandoned once it runs out of the
+/// time the options allow
+fn solve_in_time(
+    day: &Day,
+    options: &RunOptions,
+    input: String,
+) -> Result<(Samples, Vec<PartReport>), AocError> {
+    match options.timeout {
+        Some(limit) => Solving::start(day, options, input).wait(Some(limit)),
+        None => solve_input(day, options, &input),
+    }
+}
+
+/// loads the input of a day and solves it within the time limit of the options
+fn solve_day(day: &Day, options: &RunOptions, input_dir: &Path) -> DayReport {
+    let number = day.number;
+    let result = read_input(number, &options.input, input_dir)
+        .map_err(AocError::from)
+        .and_then(|input| solve_in_time(day, options, input))
+        .map_err(|err| err.in_day(number));
+    DayReport {
+        day: number,
+        result,
+    }
+}
+
+/// runs the registered days selected by `options` against their input
+///
+/// inputs are loaded from `input_dir`, days without an input are reported and skipped.
+/// answers for the default inputs are checked against `answers.txt` in `input_dir`,
+/// answers for the examples against the ones from the puzzle description.
+///
+/// with more than one job the days are solved in parallel, but still printed in order
+pub fn run(registry: &Registry, options: &RunOptions, input_dir: &Path) {
+    let format = options.format;
+    let answers_path = answers_path(input_dir);
+    let mut answers = match options.input {
+        InputSource::Default => match Answers::load(&answers_path) {
+            Ok(answers) => Some(answers),
+            Err(err) => {
+                notice(format, format!("Invalid answers file: {}", err).red());
+                None
+            }
+        },
+        _ => None,
+    };
+    let mut verdicts = Vec::new();
+    let mut recorded = Vec::new();
+    let mut total = Duration::ZERO;
+    let text = format == Format::Text;
+    let width = terminal::width();
+    let timed = text && options.timing != Timing::Off;
+    for line in format.header() {
+        println!("{}", line);
+    }
+    let start = Instant::now();
+    let days: Vec<&Day> = registry
+        .iter()
+        .filter(|day| options.runs_day(day.number))
+        .collect();
+    let solve = |day: &&Day| solve_day(day, options, input_dir);
+    pool::ordered(&days, options.jobs, solve, |report| {
+        if text {
+            sep(report.day, width);
+        }
+        let (samples, parts) = match report.result {
+            Ok(result) => result,
+            Err(err) => {
+                print_error(format, &err);
+                return;
+            }
+        };
+        if timed {
+            print_time("parse", &samples);
+            total += samples.median();
+        }
+        for PartReport {
+            part,
+            output,
+            samples,
+        } in parts
+        {
+            match output {
+                Ok(output) => {
+                    match format {
+                        Format::Text => output.print(),
+                        _ => format
+                            .render(report.day, &output)
+                            .iter()
+                            .for_each(|line| println!("{}", line)),
+                    }
+                    if timed {
+                        print_time(&format!("part {}", part), &samples);
+                        total += samples.median();
+                    }
+                    let answer = output.answer_value();
+                    let verdict = verdict(
+                        &options.input,
+                        answers.as_ref(),
+                        report.day,
+                        part,
+                        answer.as_deref(),
+                    );
+                    if let Some(verdict) = verdict {
+                        if text {
+                            verdict.print();
+                        }
+                        verdicts.push(verdict);
+                    }
+                    if let Some(answer) = answer {
+                        recorded.push((report.day, part, answer));
+                    }
+                }
+                Err(err) => print_error(format, &err),
+            }
+        }
+    });
+    if text && !verdicts.is_empty() {
+        print_summary(&verdicts);
+    }
+    if timed {
+        println!();
+        println!("{}", format!("Total: {:.2?}", total).bold());
+        if options.jobs > 1 {
+            let wall_clock = format!("Wall clock: {:.2?}", start.elapsed());
+            println!("{}", wall_clock.bold());
+        }
+    }
+    if !options.record {
+        return;
+    }
+    match answers.as_mut() {
+        Some(answers) => {
+            for (day, part, answer) in recorded.iter() {
+                answers.insert(*day, *part, answer);
+            }
+            match answers.save(&answers_path) {
+                Ok(()) => notice(
+                    format,
+                    format!(
+                        "Recorded {} answers in {}",
+                        recorded.len(),
+                        answers_path.display()
+                    )
+                    .normal(),
+                ),
+                Err(err) => {
+                    let err = format!("Could not write {}: {}", answers_path.display(), err);
+                    notice(format, err.red())
+                }
+            }
+        }
+        None => notice(format, "Not recording, the answers file is invalid".red()),
+    }
+}
+
+/// the input files of the days selected by `options` and their examples in
+/// `examples_dir`
+fn watched_paths(options: &RunOptions, input_dir: &Path, examples_dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for day in options.days.iter().flat_map(|days| days.clone()) {
+        let input = match options.input {
+            InputSource::Example => None,
+            ref source => input_file(day, source, input_dir),
+        };
+        for path in input
+            .into_iter()
+            .chain([fixtures::example_in(examples_dir, day)])
+        {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// runs the days selected by `options` with timing, and again every time
+/// their input or their example in `examples_dir` changes
+pub fn watch(
+    registry: &Registry,
+    options: &RunOptions,
+    input_dir: &Path,
+    examples_dir: &Path,
+) -> ! {
+    let paths = watched_paths(options, input_dir, examples_dir);
+    let options = RunOptions {
+        timing: match options.timing {
+            Timing::Off => Timing::Once,
+            timing => timing,
+        },
+        ..options.clone()
+    };
+    watch::watch(Watcher::new(paths), || run(registry, &options, input_dir))
+}
+
+/// parses the input of the single day selected by `options`, for its visualization
+fn parse_single_day(
+    registry: &Registry,
+    options: &RunOptions,
+    input_dir: &Path,
+) -> Result<(usize, Box<dyn Solution>), String> {
+    let number = *options
+        .days
+        .as_ref()
+        .expect("a single day is selected")
+        .start();
+    let day = registry
+        .get(number)
+        .ok_or_else(|| format!("Day {} is not registered", number))?;
+    let input = read_input(number, &options.input, input_dir).map_err(|err| err.to_string())?;
+    let solution = day.parse(&input).map_err(|err| err.to_string())?;
+    if solution.visualization().is_none() {
+        return Err(format!("Day {} has no visualization", number));
+    }
+    Ok((number, solution))
+}
+
+/// Animates the visualization of the single day selected by `options` on its input
+pub fn visualize(
+    registry: &Registry,
+    options: &RunOptions,
+    input_dir: &Path,
+) -> Result<(), String> {
+    let (_, solution) = parse_single_day(registry, options, input_dir)?;
+    let visualization = solution.visualization().unwrap();
+    visualize::play(visualization).map_err(|err| format!("Could not animate: {}", err))
+}
+
+/// Writes the visualization of the single day selected by `options` to the
+/// image file of `--export`
+pub fn export(registry: &Registry, options: &RunOptions, input_dir: &Path) -> Result<(), String> {
+    let path = options.export.as_ref().expect("an image file is given");
+    let (number, solution) = parse_single_day(registry, options, input_dir)?;
+    let visualization = solution.visualization().unwrap();
+    export::export(visualization, path, &options.palette, options.cell_size).map_err(|err| {
+        format!(
+            "Could not export day {} to {}: {}",
+            number,
+            path.display(),
+            err
+        )
+    })
+}
+
+/// Solves a part of a day on its input and submits the answer, the server's
+/// verdict is remembered in `answers.txt` of `input_dir`.
+///
+/// Returns the submitted answer and what the server said about it.
+pub fn submit(
+    registry: &Registry,
+    config: &Config,
+    input_dir: &Path,
+    day: usize,
+    part: u8,
+) -> Result<(String, Outcome), String> {
+    let number = day;
+    let day = registry
+        .get(number)
+        .ok_or_else(|| format!("day {} is not registered", number))?;
+    let input =
+        read_input(number, &InputSource::Default, input_dir).map_err(|err| err.to_string())?;
+    let output = day
+        .parse(&input)
+        .and_then(|solution| day.solve(solution.as_ref(), part))
+        .map_err(|err| err.in_day(number).to_string())?;
+    let answer = output
+        .answer_value()
+        .ok_or_else(|| format!("part {} has no answer", part))?;
+    let path = answers_path(input_dir);
+    let mut answers = Answers::load(&path)?;
+    let outcome = submit_answer(
+        &Client::from_config(config)?,
+        &mut answers,
+        number,
+        part,
+        &answer,
+    )?;
+    answers
+        .save(&path)
+        .map_err(|err| format!("could not write {}: {}", path.display(), err))?;
+    Ok((answer.trim().to_string(), outcome))
+}
+
+/// prints the numbers of all registered days
+pub fn list(registry: &Registry) {
+    for day in registry.iter() {
+        println!("Day {}", day.number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixtures::example_in, watch::Watcher, watched_paths};
+    use crate::cli::{InputSource, RunOptions};
+    use std::{env, fs, path::Path, process};
+
+    #[test]
+    fn editing_the_example_triggers_a_run() {
+        let examples = env::temp_dir().join(format!("aoc-examples-{}", process::id()));
+        let _ = fs::remove_dir_all(&examples);
+        let example = example_in(&examples, 1);
+        let options = RunOptions {
+            days: Some(1..=1),
+            input: InputSource::File("day1.txt".into()),
+            ..RunOptions::default()
+        };
+        let paths = watched_paths(&options, Path::new("inputs"), &examples);
+        assert_eq!(paths, [Path::new("day1.txt"), example.as_path()]);
+        let mut watcher = Watcher::new(paths);
+        fs::create_dir_all(&examples).unwrap();
+        fs::write(&example, "1").unwrap();
+        assert!(watcher.changed());
+        fs::write(&example, "1\n2").unwrap();
+        assert!(watcher.changed());
+        fs::remove_dir_all(&examples).unwrap();
+        let options = RunOptions {
+            input: InputSource::Example,
+            ..options
+        };
+        let paths = watched_paths(&options, Path::new("inputs"), &examples);
+        assert_eq!(paths, [example]);
+    }
 }
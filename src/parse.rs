@@ -0,0 +1,315 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till1},
+    character::complete::{char, digit1, line_ending, space0, space1},
+    combinator::{cut, eof, map_res, not, opt, recognize, value},
+    error::{context, ContextError, ErrorKind, FromExternalError, ParseError},
+    multi::{many1_count, separated_list1},
+    sequence::{pair, preceded, separated_pair, terminated},
+    IResult, Parser,
+};
+
+use crate::{error::AocError, grid::Grid};
+
+/// A parse error, converted into a located `AocError` by `parse_all`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<'a> {
+    /// the rest of the input, starting where the error occurred
+    pub fragment: &'a str,
+    pub message: String,
+    /// whether the message was given by a parser rather than derived from nom's error kind
+    described: bool,
+}
+
+impl<'a> Error<'a> {
+    pub fn new(fragment: &'a str, message: impl Into<String>) -> Error<'a> {
+        Error {
+            fragment,
+            message: message.into(),
+            described: true,
+        }
+    }
+}
+
+impl<'a> ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(fragment: &'a str, kind: ErrorKind) -> Self {
+        let message = match kind {
+            ErrorKind::Digit => "Expected a digit",
+            ErrorKind::Alpha | ErrorKind::AlphaNumeric => "Expected a letter",
+            ErrorKind::Space | ErrorKind::MultiSpace => "Expected a space",
+            ErrorKind::CrLf => "Expected a line break",
+            ErrorKind::Eof => "Expected the end of the input",
+            _ => "Unexpected input",
+        };
+        Error {
+            fragment,
+            message: message.into(),
+            described: false,
+        }
+    }
+
+    fn from_char(fragment: &'a str, c: char) -> Self {
+        Error {
+            fragment,
+            message: format!("Expected '{}'", c),
+            described: false,
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+/// the innermost context describes the error, without one nom's error kind is used
+impl<'a> ContextError<&'a str> for Error<'a> {
+    fn add_context(_: &'a str, context: &'static str, mut other: Self) -> Self {
+        if !other.described {
+            other.message = context.into();
+            other.described = true;
+        }
+        other
+    }
+}
+
+impl<'a, E: Display> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(fragment: &'a str, _: ErrorKind, err: E) -> Self {
+        Error::new(fragment, err.to_string())
+    }
+}
+
+pub type PResult<'a, T> = IResult<&'a str, T, Error<'a>>;
+
+/// parses the whole input with `parser`, only whitespace may be left over
+///
+/// errors point to their position in the input
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, T, Error<'a>>,
+) -> Result<T, AocError> {
+    match parser.parse(input) {
+        Ok((rest, value)) => match rest.trim_start() {
+            "" => Ok(value),
+            rest => Err(AocError::parse(input, rest, "Unexpected input")),
+        },
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            Err(AocError::parse(input, err.fragment, err.message))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(AocError::invalid("The input is incomplete")),
+    }
+}
+
+/// an integer, negative numbers start with `-`
+pub fn number<'a, T>(input: &'a str) -> PResult<'a, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    context(
+        "Expected a number",
+        map_res(recognize(pair(opt(char('-')), digit1)), str::parse),
+    )(input)
+}
+
+/// the rest of a line, which must not be empty
+pub fn line(input: &str) -> PResult<'_, &str> {
+    context("Expected a line", take_till1(|c| c == '\n' || c == '\r'))(input)
+}
+
+/// items separated by commas, like `3,4,3,1,2`
+pub fn comma_list<'a, T>(
+    item: impl Parser<&'a str, T, Error<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(char(','), cut(item))
+}
+
+/// items separated by spaces, leading spaces are skipped
+pub fn space_list<'a, T>(
+    item: impl Parser<&'a str, T, Error<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    preceded(space0, separated_list1(space1, item))
+}
+
+/// one item per line, stops at a blank line or the end of the input
+pub fn lines<'a, T>(
+    item: impl Parser<&'a str, T, Error<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    let separator = terminated(line_ending, not(alt((line_ending, eof))));
+    separated_list1(separator, cut(item))
+}
+
+/// a rule `left -> right`, like the vents of day 5
+pub fn arrow<'a, L, R>(
+    left: impl Parser<&'a str, L, Error<'a>>,
+    right: impl Parser<&'a str, R, Error<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, (L, R)> {
+    separated_pair(left, context("Expected ' -> '", tag(" -> ")), right)
+}
+
+/// one or more empty lines after a line
+fn blank_lines(input: &str) -> PResult<'_, ()> {
+    value((), pair(line_ending, many1_count(line_ending)))(input)
+}
+
+/// sections separated by blank lines, like the bingo boards of day 4
+pub fn sections<'a, T>(
+    section: impl Parser<&'a str, T, Error<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(terminated(blank_lines, not(eof)), cut(section))
+}
+
+/// two different sections separated by blank lines, like the dots and folds of day 13
+pub fn two_sections<'a, A, B>(
+    first: impl Parser<&'a str, A, Error<'a>>,
+    second: impl Parser<&'a str, B, Error<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, (A, B)> {
+    separated_pair(
+        first,
+        cut(context("Expected a blank line", blank_lines)),
+        cut(second),
+    )
+}
+
+/// a grid with one row per line, `cell` turns the characters into cells.
+///
+/// Characters `cell` rejects are reported with the message `expected`.
+pub fn char_grid<'a, T>(
+    cell: impl Fn(char) -> Option<T>,
+    expected: &'static str,
+) -> impl FnMut(&'a str) -> PResult<'a, Grid<T>> {
+    move |input| {
+        let row = |input: &'a str| {
+            let (rest, line) = line(input)?;
+            // trailing spaces are not part of the grid
+            let line = line.trim_end();
+            let row = line
+                .char_indices()
+                .map(|(i, c)| cell(c).ok_or_else(|| Error::new(&line[i..], expected)))
+                .collect::<Result<Vec<T>, Error>>()
+                .map_err(nom::Err::Failure)?;
+            Ok((rest, (line, row)))
+        };
+        let (rest, rows) = lines(row)(input)?;
+        let (lines, rows): (Vec<&str>, Vec<Vec<T>>) = rows.into_iter().unzip();
+        let grid = Grid::from_rows(rows).map_err(|row| {
+            nom::Err::Failure(Error::new(lines[row], "Lines not of the same length"))
+        })?;
+        Ok((rest, grid))
+    }
+}
+
+/// a grid of single digits like `2199943210`
+pub fn digit_grid(input: &str) -> PResult<'_, Grid<u8>> {
+    char_grid(|c| c.to_digit(10).map(|d| d as u8), "Expected a digit")(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        arrow, comma_list, digit_grid, lines, number, parse_all, sections, space_list, two_sections,
+    };
+    use crate::error::AocError;
+    use nom::character::complete::{alpha1, char};
+    use nom::sequence::separated_pair;
+
+    /// the line, column and message of a parse error
+    fn error<T>(result: Result<T, AocError>) -> (usize, usize, String) {
+        match result {
+            Err(AocError::Parse {
+                location: Some(location),
+                message,
+                ..
+            }) => (location.line, location.column, message),
+            Err(err) => panic!("Expected a located parse error, got {}", err),
+            Ok(_) => panic!("Expected a located parse error"),
+        }
+    }
+
+    #[test]
+    fn numbers_and_lists() {
+        assert_eq!(parse_all("-12", number::<i32>).unwrap(), -12);
+        assert_eq!(
+            parse_all("1\n2\n3\n", lines(number::<u32>)).unwrap(),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            parse_all("3,4,3", comma_list(number::<u8>)).unwrap(),
+            [3, 4, 3]
+        );
+        assert_eq!(
+            parse_all(" 8  2 23", space_list(number::<u8>)).unwrap(),
+            [8, 2, 23]
+        );
+        assert_eq!(
+            error(parse_all("1\n2\nthree", lines(number::<u32>))),
+            (3, 1, "Expected a number".into())
+        );
+        assert_eq!(
+            error(parse_all("3,x", comma_list(number::<u8>))),
+            (1, 3, "Expected a number".into())
+        );
+        assert_eq!(
+            error(parse_all("300", number::<u8>)),
+            (1, 1, "number too large to fit in target type".into())
+        );
+        assert_eq!(
+            error(parse_all("12 3", number::<u8>)),
+            (1, 4, "Unexpected input".into())
+        );
+    }
+
+    #[test]
+    fn rules_and_sections() {
+        let point = || separated_pair(number::<u32>, char(','), number::<u32>);
+        assert_eq!(
+            parse_all("0,9 -> 5,9", arrow(point(), point())).unwrap(),
+            ((0, 9), (5, 9))
+        );
+        assert_eq!(
+            error(parse_all("0,9 => 5,9", arrow(point(), point()))),
+            (1, 4, "Expected ' -> '".into())
+        );
+        let boards = parse_all(
+            "1 2\n3 4\n\n\n5 6\n7 8\n",
+            sections(lines(space_list(number::<u8>))),
+        );
+        assert_eq!(
+            boards.unwrap(),
+            [vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]]
+        );
+        let (template, rules) = parse_all(
+            "NNCB\n\nCH -> B\nHH -> N",
+            two_sections(alpha1, lines(arrow(alpha1, alpha1))),
+        )
+        .unwrap();
+        assert_eq!(template, "NNCB");
+        assert_eq!(rules, [("CH", "B"), ("HH", "N")]);
+        assert_eq!(
+            error(parse_all(
+                "NNCB\nCH -> B",
+                two_sections(alpha1, lines(arrow(alpha1, alpha1)))
+            )),
+            (2, 1, "Expected a blank line".into())
+        );
+    }
+
+    #[test]
+    fn digit_grids() {
+        let grid = parse_all("123\n456\n", digit_grid).unwrap();
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(
+            error(parse_all("123\n4x6", digit_grid)),
+            (2, 2, "Expected a digit".into())
+        );
+        assert_eq!(
+            error(parse_all("123\n45", digit_grid)),
+            (2, 1, "Lines not of the same length".into())
+        );
+        assert_eq!(
+            error(parse_all("\n", digit_grid)),
+            (1, 1, "Expected a line".into())
+        );
+    }
+}
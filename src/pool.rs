@@ -0,0 +1,135 @@
+use std::{
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// the number of threads used when no number is given
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |jobs| jobs.get())
+}
+
+/// Applies `work` to every item on a pool of `jobs` threads and hands the
+/// results to `consume` in the order of the items.
+///
+/// Results are buffered until all the results before them are consumed, so
+/// `consume` runs on the calling thread as soon as the next result in order
+/// is available. With a single job everything runs on the calling thread.
+pub fn ordered<T, R, W, C>(items: &[T], jobs: usize, work: W, mut consume: C)
+where
+    T: Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    C: FnMut(R),
+{
+    if jobs <= 1 || items.len() <= 1 {
+        items.iter().map(&work).for_each(consume);
+        return;
+    }
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(items.len()) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if sender.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        let mut pending = BTreeMap::new();
+        let mut consumed = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&consumed) {
+                consume(result);
+                consumed += 1;
+            }
+        }
+    });
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed number of threads running jobs one after the other, while all of
+/// them are busy up to `queue` jobs wait for a free thread
+pub struct Workers {
+    jobs: SyncSender<Job>,
+}
+
+impl Workers {
+    pub fn new(threads: usize, queue: usize) -> Workers {
+        let (jobs, receiver) = mpsc::sync_channel::<Job>(queue);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..threads.max(1) {
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || loop {
+                // the lock is only held while waiting for the next job
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    // a panicking job must not take its thread with it
+                    Ok(job) => drop(panic::catch_unwind(AssertUnwindSafe(job))),
+                    Err(_) => return,
+                }
+            });
+        }
+        Workers { jobs }
+    }
+
+    /// hands `job` to a free thread or the queue, `false` if both are full
+    pub fn try_run(&self, job: impl FnOnce() + Send + 'static) -> bool {
+        self.jobs.try_send(Box::new(job)).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ordered, Workers};
+    use std::{sync::mpsc, thread, time::Duration};
+
+    #[test]
+    fn results_stay_in_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let mut results = Vec::new();
+        ordered(
+            &items,
+            4,
+            |item| {
+                // make the early items finish last
+                thread::sleep(Duration::from_millis(20 - item));
+                item * 2
+            },
+            |result| results.push(result),
+        );
+        assert_eq!(results, (0..20).map(|item| item * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn workers_refuse_jobs_past_the_queue() {
+        let workers = Workers::new(1, 1);
+        let (started, running) = mpsc::channel();
+        let (release, blocked) = mpsc::channel::<()>();
+        assert!(workers.try_run(move || {
+            started.send(()).unwrap();
+            let _ = blocked.recv();
+        }));
+        running.recv().unwrap();
+        let (done, finished) = mpsc::channel();
+        assert!(workers.try_run(move || done.send(()).unwrap()));
+        // the thread is busy and the queue is full
+        assert!(!workers.try_run(|| {}));
+        drop(release);
+        finished.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::{gen, registry};
+
+/// A slow but obviously correct solver of one part, it may panic on invalid inputs
+type Solver = fn(&str) -> u64;
+
+/// the parts that have a reference solver, the parts that take too long to
+/// solve naively (like 256 days of lanternfish) have none
+const REFERENCES: &[(usize, u8, Solver)] = &[
+    (6, 1, day6_part1),
+    (8, 1, day8_part1),
+    (8, 2, day8_part2),
+    (14, 1, day14_part1),
+];
+
+/// the days that have at least one reference solver
+pub fn days() -> BTreeSet<usize> {
+    REFERENCES.iter().map(|(day, _, _)| *day).collect()
+}
+
+/// lanternfish after 80 days, every fish is simulated on its own
+fn day6_part1(input: &str) -> u64 {
+    let mut fish: Vec<u8> = input.split(',').map(|n| n.parse().unwrap()).collect();
+    for _ in 0..80 {
+        let mut spawned = 0;
+        for timer in fish.iter_mut() {
+            match timer {
+                0 => {
+                    *timer = 6;
+                    spawned += 1;
+                }
+                _ => *timer -= 1,
+            }
+        }
+        fish.extend(std::iter::repeat_n(8, spawned));
+    }
+    fish.len() as u64
+}
+
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// all orders of `items`
+fn permutations(items: &[char]) -> Vec<Vec<char>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    let mut orders = Vec::new();
+    for (i, first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(i);
+        for mut order in permutations(&rest) {
+            order.insert(0, *first);
+            orders.push(order);
+        }
+    }
+    orders
+}
+
+/// the digit shown by `pattern` if wire `a + i` is connected to segment `wiring[i]`
+fn decode(wiring: &[char], pattern: &str) -> Option<usize> {
+    let mut segments: Vec<char> = pattern
+        .chars()
+        .map(|wire| wiring[(wire as u8 - b'a') as usize])
+        .collect();
+    segments.sort_unstable();
+    let segments: String = segments.into_iter().collect();
+    DIGITS.iter().position(|digit| *digit == segments)
+}
+
+/// the output digits of every display, found by trying all 5040 wirings
+fn day8_outputs(input: &str) -> Vec<Vec<usize>> {
+    let wirings = permutations(&['a', 'b', 'c', 'd', 'e', 'f', 'g']);
+    input
+        .lines()
+        .map(|line| {
+            let (patterns, output) = line.split_once(" | ").unwrap();
+            let wiring = wirings
+                .iter()
+                // the ten patterns differ, so if all of them are digits, all digits are shown
+                .find(|wiring| {
+                    let mut patterns = patterns.split(' ');
+                    patterns.all(|pattern| decode(wiring, pattern).is_some())
+                })
+                .expect("no wiring shows all ten digits");
+            output
+                .split(' ')
+                .map(|pattern| decode(wiring, pattern).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+/// how many 1s, 4s, 7s and 8s are shown
+fn day8_part1(input: &str) -> u64 {
+    let outputs = day8_outputs(input);
+    let digits = outputs.iter().flatten();
+    digits.filter(|digit| [1, 4, 7, 8].contains(*digit)).count() as u64
+}
+
+/// the sum of the shown numbers
+fn day8_part2(input: &str) -> u64 {
+    let outputs = day8_outputs(input).into_iter();
+    let numbers = outputs.map(|digits| digits.iter().fold(0, |n, &digit| 10 * n + digit as u64));
+    numbers.sum()
+}
+
+/// the spread of the elements after 10 steps, the polymer is built as a string
+fn day14_part1(input: &str) -> u64 {
+    let (template, rules) = input.split_once("\n\n").unwrap();
+    let rules: HashMap<&str, char> = rules
+        .lines()
+        .map(|rule| {
+            let (pair, element) = rule.split_once(" -> ").unwrap();
+            (pair, element.chars().next().unwrap())
+        })
+        .collect();
+    let mut polymer = template.to_string();
+    for _ in 0..10 {
+        let mut next = String::new();
+        for (i, element) in polymer.char_indices() {
+            next.push(element);
+            if let Some(insert) = polymer.get(i..i + 2).and_then(|pair| rules.get(pair)) {
+                next.push(*insert);
+            }
+        }
+        polymer = next;
+    }
+    let mut counts: HashMap<char, u64> = HashMap::new();
+    for element in polymer.chars() {
+        *counts.entry(element).or_insert(0) += 1;
+    }
+    counts.values().max().unwrap() - counts.values().min().unwrap()
+}
+
+/// The first generated input on which a solution and its reference disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub day: usize,
+    pub part: u8,
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    pub expected: String,
+    /// the answer of the solution, or the error it failed with
+    pub actual: String,
+}
+
+impl Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {} part {} (seed {}, size {}): expected {}, got {}",
+            self.day, self.part, self.seed, self.size, self.expected, self.actual
+        )?;
+        write!(f, "{}", self.input)
+    }
+}
+
+/// solves the inputs generated for `day` from all `seeds` with the solution and
+/// the reference solvers, returns how many answers were compared
+pub fn compare(day: usize, seeds: Range<u64>, size: usize) -> Result<usize, Disagreement> {
+    let registry = registry();
+    let solution = registry.get(day).expect("the day is registered");
+    let mut compared = 0;
+    for seed in seeds {
+        let input = gen::generate(day, seed, size).expect("the day has a generator");
+        let parsed = solution.parse(&input);
+        for (_, part, reference) in REFERENCES.iter().filter(|(d, _, _)| *d == day) {
+            let actual = match &parsed {
+                Ok(parsed) => match solution.solve(parsed.as_ref(), *part) {
+                    Ok(output) => output.answer_value().unwrap_or_default(),
+                    Err(err) => err.to_string(),
+                },
+                Err(err) => err.to_string(),
+            };
+            let expected = reference(&input).to_string();
+            if actual != expected {
+                return Err(Disagreement {
+                    day,
+                    part: *part,
+                    seed,
+                    size,
+                    input,
+                    expected,
+                    actual,
+                });
+            }
+            compared += 1;
+        }
+    }
+    Ok(compared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, day14_part1, days, Disagreement, REFERENCES};
+    use crate::{fixtures::fixture, helper::normalize, registry};
+
+    /// the references agree with the answers of the puzzle examples
+    #[test]
+    fn references_solve_the_examples() {
+        for (day, part, reference) in REFERENCES {
+            let fixture = fixture(*day).unwrap();
+            let expected = fixture.expected(*part).unwrap();
+            let answer = reference(&normalize(fixture.input));
+            assert_eq!(answer.to_string(), expected, "day {} part {}", day, part);
+        }
+    }
+
+    #[test]
+    fn solutions_agree_with_references() {
+        for day in days() {
+            if let Err(disagreement) = compare(day, 0..20, 10) {
+                panic!("{}", disagreement);
+            }
+        }
+    }
+
+    /// pairs without a rule are kept by both solvers
+    #[test]
+    fn partial_rules_agree() {
+        let input = "NNCB\n\nNN -> C\nCB -> H\nHB -> N";
+        let solution = registry();
+        let day = solution.get(14).unwrap();
+        let parsed = day.parse(input).unwrap();
+        let output = day.solve(parsed.as_ref(), 1).unwrap();
+        assert_eq!(output.answer_value(), Some(day14_part1(input).to_string()));
+    }
+
+    #[test]
+    fn disagreements_show_the_input() {
+        let disagreement = Disagreement {
+            day: 6,
+            part: 1,
+            seed: 3,
+            size: 2,
+            input: "3,4".into(),
+            expected: "10".into(),
+            actual: "11".into(),
+        };
+        assert_eq!(
+            disagreement.to_string(),
+            "day 6 part 1 (seed 3, size 2): expected 10, got 11\n3,4"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+
+use crate::helper::{Answer, OutputFormatter};
+
+/// How the results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// colored text for humans, printed by `OutputFormatter::print`
+    Text,
+    /// one JSON object per field
+    JsonLines,
+    Csv,
+    /// a Markdown table
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::JsonLines),
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!("Invalid format: '{}'", format)),
+        }
+    }
+}
+
+impl Format {
+    /// the lines printed before the first record
+    pub fn header(&self) -> Vec<String> {
+        match self {
+            Format::Text | Format::JsonLines => Vec::new(),
+            Format::Csv => vec!["day,task,field,value,answer".into()],
+            Format::Markdown => vec![
+                "| Day | Task | Field | Value | Answer |".into(),
+                "| ---: | ---: | --- | --- | :---: |".into(),
+            ],
+        }
+    }
+
+    /// one line per field of the output, nothing for text which is
+    /// printed by `OutputFormatter::print` instead
+    pub fn render(&self, day: usize, output: &OutputFormatter) -> Vec<String> {
+        output
+            .fields()
+            .filter_map(|(name, value)| {
+                let answer = output.is_answer(name);
+                match self {
+                    Format::Text => None,
+                    Format::JsonLines => Some(format!(
+                        r#"{{"day":{},"task":{},"field":{},"value":{},"answer":{}}}"#,
+                        day,
+                        output.task(),
+                        json_string(name),
+                        json_value(value),
+                        answer
+                    )),
+                    Format::Csv => Some(format!(
+                        "{},{},{},{},{}",
+                        day,
+                        output.task(),
+                        csv_field(name),
+                        csv_value(value),
+                        answer
+                    )),
+                    Format::Markdown => Some(format!(
+                        "| {} | {} | {} | {} | {} |",
+                        day,
+                        output.task(),
+                        markdown_cell(name),
+                        markdown_value(value),
+                        if answer { "✓" } else { "" }
+                    )),
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_value(value: &Answer) -> String {
+    match value {
+        Answer::Integer(value) => value.to_string(),
+        Answer::Str(text) => json_string(text),
+        // tagged, so a grid can be told apart from a list of strings
+        Answer::Grid(rows) => {
+            let rows: Vec<String> = rows.iter().map(|row| json_string(row)).collect();
+            format!(r#"{{"type":"grid","rows":[{}]}}"#, rows.join(","))
+        }
+        Answer::List(values) => {
+            let values: Vec<String> = values.iter().map(json_value).collect();
+            format!("[{}]", values.join(","))
+        }
+        Answer::None => "null".into(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+fn csv_value(value: &Answer) -> String {
+    match value {
+        Answer::Integer(value) => value.to_string(),
+        Answer::None => String::new(),
+        value => csv_field(&value.to_string()),
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn markdown_value(value: &Answer) -> String {
+    match value {
+        Answer::Integer(value) => value.to_string(),
+        Answer::None => String::new(),
+        // table cells can not contain line breaks
+        Answer::Grid(rows) => format!("<pre>{}</pre>", markdown_cell(&rows.join("<br>"))),
+        value => format!("`{}`", markdown_cell(&value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+    use crate::helper::{out, Answer};
+
+    #[test]
+    fn render_records() {
+        let output = out(2).answer("code", Answer::grid("#.\n.#"));
+        assert_eq!(
+            Format::JsonLines.render(13, &output),
+            vec![
+                r##"{"day":13,"task":2,"field":"code","value":{"type":"grid","rows":["#.",".#"]},"answer":true}"##
+            ]
+        );
+        assert_eq!(
+            Format::Csv.render(13, &output),
+            vec!["13,2,code,\"#.\n.#\",true"]
+        );
+        let output = out(1).var("sum", 42u64).var("position", [3, 4]);
+        assert_eq!(
+            Format::Markdown.render(1, &output),
+            vec![
+                "| 1 | 1 | sum | 42 |  |",
+                "| 1 | 1 | position | `[3, 4]` |  |"
+            ]
+        );
+        assert_eq!(
+            Format::JsonLines.render(1, &output)[1],
+            r#"{"day":1,"task":1,"field":"position","value":[3,4],"answer":false}"#
+        );
+        assert!(Format::Text.render(1, &output).is_empty());
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!("md".parse(), Ok(Format::Markdown));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
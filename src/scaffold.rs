@@ -0,0 +1,210 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// the directory of the crate whose source is extended, this is the current
+/// directory if it contains `src/lib.rs` and the directory the binary was
+/// built from otherwise
+pub fn crate_root() -> PathBuf {
+    match Path::new("src/lib.rs").is_file() {
+        true => PathBuf::from("."),
+        false => PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+    }
+}
+
+/// the module of a new day, with stubs for parsing and both parts
+pub fn template(day: usize) -> String {
+    format!(
+        r#"use crate::error::AocError;
+use crate::helper::{{out, OutputFormatter}};
+use crate::parse::{{line, lines, parse_all}};
+use crate::solution::Solution;
+
+pub struct Day{day} {{
+    lines: Vec<String>,
+}}
+
+impl Solution for Day{day} {{
+    fn parse(input: &str) -> Result<Self, AocError> {{
+        Ok(Day{day} {{
+            lines: parse_all(input, lines(line))?
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }})
+    }}
+
+    fn part1(&self) -> Result<OutputFormatter, AocError> {{
+        Ok(out(1).answer("number of lines", self.lines.len()))
+    }}
+
+    fn part2(&self) -> Result<OutputFormatter, AocError> {{
+        todo!()
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::Day{day};
+    use crate::solution::Solution;
+
+    const EXAMPLE: &str = include_str!("../inputs/examples/day{day}.txt");
+
+    #[test]
+    #[ignore = "add the example and its answers"]
+    fn example() {{
+        let day = Day{day}::parse(EXAMPLE).unwrap();
+        assert_eq!(day.part1().unwrap().answer_value(), Some("".into()));
+        assert_eq!(day.part2().unwrap().answer_value(), Some("".into()));
+    }}
+}}
+"#,
+        day = day
+    )
+}
+
+fn day_number(line: &str, prefix: &str, suffix: &str) -> Option<usize> {
+    line.trim()
+        .strip_prefix(prefix)?
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+        .filter(|_| line.trim().ends_with(suffix))
+}
+
+/// adds the module declaration and the registration of `day` to the source of `lib.rs`
+pub fn register(lib: &str, day: usize) -> Result<String, String> {
+    let module = format!("pub mod day{};", day);
+    let registration = format!("        .register::<day{day}::Day{day}>({day})", day = day);
+    let mut lines: Vec<String> = lib.lines().map(str::to_string).collect();
+    if lines.iter().any(|line| line.trim() == module) {
+        return Err(format!("day {} is already declared in lib.rs", day));
+    }
+    // modules are sorted by name, like rustfmt does
+    let name = format!("day{}", day);
+    let modules: Vec<(usize, String)> = (0..lines.len())
+        .filter_map(|i| {
+            let number = day_number(&lines[i], "pub mod day", ";")?;
+            Some((i, format!("day{}", number)))
+        })
+        .collect();
+    let position = match modules.iter().find(|(_, module)| *module > name) {
+        Some((i, _)) => *i,
+        None => match modules.last() {
+            Some((i, _)) => i + 1,
+            None => return Err("found no day modules in lib.rs".into()),
+        },
+    };
+    lines.insert(position, module);
+    // registrations are sorted by day
+    let registrations: Vec<(usize, usize)> = (0..lines.len())
+        .filter_map(|i| Some((i, day_number(&lines[i], ".register::<day", ")")?)))
+        .collect();
+    let position = match registrations.iter().find(|(_, number)| *number > day) {
+        Some((i, _)) => *i,
+        None => match registrations.last() {
+            Some((i, _)) => i + 1,
+            None => return Err("found no registered days in lib.rs".into()),
+        },
+    };
+    lines.insert(position, registration);
+    let mut lib = lines.join("\n");
+    lib.push('\n');
+    Ok(lib)
+}
+
+/// the files `new_day` creates
+pub struct Scaffold {
+    pub module: PathBuf,
+    pub example: PathBuf,
+    pub input: PathBuf,
+    pub lib: PathBuf,
+}
+
+impl Scaffold {
+    /// `root` is the directory containing `Cargo.toml`
+    pub fn new(root: &Path, input_dir: &Path, day: usize) -> Scaffold {
+        Scaffold {
+            module: root.join("src").join(format!("day{}.rs", day)),
+            example: root
+                .join("inputs")
+                .join("examples")
+                .join(format!("day{}.txt", day)),
+            input: input_dir.join(format!("day{}.txt", day)),
+            lib: root.join("src").join("lib.rs"),
+        }
+    }
+}
+
+/// writes the module, an empty example and an empty input for a new day and
+/// registers it in `lib.rs`.
+///
+/// Existing modules and examples are never overwritten, an existing input is kept.
+/// Returns the paths of the files that were written.
+pub fn new_day(scaffold: &Scaffold, day: usize) -> Result<Vec<PathBuf>, String> {
+    let io_err = |path: &Path, err: io::Error| format!("{}: {}", path.display(), err);
+    for path in [&scaffold.module, &scaffold.example] {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()));
+        }
+    }
+    let lib = fs::read_to_string(&scaffold.lib).map_err(|err| io_err(&scaffold.lib, err))?;
+    let lib = register(&lib, day)?;
+    let mut written = Vec::new();
+    let mut write = |path: &Path, content: &str| {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| io_err(dir, err))?;
+        }
+        fs::write(path, content).map_err(|err| io_err(path, err))?;
+        written.push(path.to_path_buf());
+        Ok::<(), String>(())
+    };
+    write(&scaffold.module, &template(day))?;
+    write(&scaffold.example, "")?;
+    if !scaffold.input.exists() {
+        write(&scaffold.input, "")?;
+    }
+    write(&scaffold.lib, &lib)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::register;
+
+    const LIB: &str = "pub mod day1;\npub mod day10;\npub mod day2;\npub mod error;
+
+pub fn registry() -> Registry {
+    Registry::new()
+        .register::<day1::Day1>(1)
+        .register::<day2::Day2>(2)
+        .register::<day10::Day10>(10)
+}
+";
+
+    #[test]
+    fn register_day() {
+        let lib = register(LIB, 3).unwrap();
+        let lines: Vec<&str> = lib.lines().collect();
+        assert_eq!(
+            lines[..5],
+            [
+                "pub mod day1;",
+                "pub mod day10;",
+                "pub mod day2;",
+                "pub mod day3;",
+                "pub mod error;"
+            ]
+        );
+        assert_eq!(lines[10], "        .register::<day3::Day3>(3)");
+        assert_eq!(lines[11], "        .register::<day10::Day10>(10)");
+        let lib = register(LIB, 17).unwrap();
+        assert!(lib.contains("pub mod day10;\npub mod day17;\npub mod day2;"));
+        assert!(
+            lib.contains(".register::<day10::Day10>(10)\n        .register::<day17::Day17>(17)\n}")
+        );
+        assert!(register(LIB, 2).is_err());
+    }
+}
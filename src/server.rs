@@ -0,0 +1,461 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    cli::RunOptions,
+    error::AocError,
+    helper::normalize,
+    pool::Workers,
+    render::json_string,
+    solution::{Day, Registry},
+    timing::{Samples, Timing},
+    PartReport, Solving,
+};
+
+/// The port `aoc serve` listens on if none is given
+pub const DEFAULT_PORT: u16 = 2021;
+
+/// Requests with larger bodies are rejected, puzzle inputs are a few kilobytes
+const MAX_BODY: usize = 10 << 20;
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections waiting for a free worker, per worker, more are refused
+const QUEUED_PER_WORKER: usize = 4;
+
+/// A status code and a JSON body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: String) -> Response {
+        Response { status, body }
+    }
+
+    fn error(status: u16, kind: &str, message: &str) -> Response {
+        let error = format!(
+            r#"{{"kind":{},"message":{}}}"#,
+            json_string(kind),
+            json_string(message)
+        );
+        Response::json(status, format!(r#"{{"error":{}}}"#, error))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+}
+
+fn micros(samples: &Samples) -> u128 {
+    samples.median().as_micros()
+}
+
+/// an error as a JSON object, parse errors include their position in the input
+fn error_json(err: &AocError) -> String {
+    let (kind, message) = match err {
+        AocError::Input(err) => ("input", err.to_string()),
+        AocError::Parse { message, .. } => ("parse", message.clone()),
+        AocError::Solve { message, .. } => ("solve", message.clone()),
+        AocError::Panic { message, .. } => ("panic", message.clone()),
+        AocError::Timeout { limit, .. } => ("timeout", format!("timed out after {:?}", limit)),
+    };
+    let location = match err {
+        AocError::Parse {
+            location: Some(location),
+            ..
+        } => format!(r#","line":{},"column":{}"#, location.line, location.column),
+        _ => String::new(),
+    };
+    format!(
+        r#"{{"kind":{},"message":{}{}}}"#,
+        json_string(kind),
+        json_string(&message),
+        location
+    )
+}
+
+fn part_json(report: &PartReport) -> String {
+    let result = match &report.output {
+        Ok(output) => match output.answer_value() {
+            Some(answer) => format!(r#""answer":{}"#, json_string(&answer)),
+            None => r#""answer":null"#.into(),
+        },
+        Err(err) => format!(r#""error":{}"#, error_json(err)),
+    };
+    format!(
+        r#"{{"part":{},{},"time_us":{}}}"#,
+        report.part,
+        result,
+        micros(&report.samples)
+    )
+}
+
+/// The solutions served over HTTP on localhost
+pub struct Server {
+    listener: TcpListener,
+    days: Arc<Vec<Day>>,
+    /// time after which a day is reported as timed out, `None` waits forever
+    timeout: Option<Duration>,
+    workers: Workers,
+}
+
+impl Server {
+    /// listens on `port` of localhost, port 0 picks a free one, at most
+    /// `workers` requests are handled at once
+    pub fn bind(
+        registry: &Registry,
+        port: u16,
+        timeout: Option<Duration>,
+        workers: usize,
+    ) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(("127.0.0.1", port))?,
+            days: Arc::new(registry.iter().copied().collect()),
+            timeout,
+            workers: Workers::new(workers, workers * QUEUED_PER_WORKER),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// answers requests on the workers until the process is stopped,
+    /// connections are refused while all workers are busy and the queue is full
+    pub fn run(self) -> ! {
+        loop {
+            let (stream, spare) = match self.listener.accept() {
+                Ok((stream, _)) => match stream.try_clone() {
+                    Ok(spare) => (stream, spare),
+                    Err(_) => continue,
+                },
+                Err(_) => continue,
+            };
+            let (days, timeout) = (Arc::clone(&self.days), self.timeout);
+            let accepted = self.workers.try_run(move || {
+                // the client is gone if the response can not be written
+                let _ = handle(stream, &days, timeout);
+            });
+            if !accepted {
+                let _ = refuse(spare);
+            }
+        }
+    }
+}
+
+/// answers right away that the server is busy, without waiting for the request
+fn refuse(mut stream: TcpStream) -> io::Result<()> {
+    // reading what has already arrived keeps the response from being lost
+    // in a reset of the connection
+    stream.set_nonblocking(true)?;
+    let mut buffer = [0; 4096];
+    while let Ok(1..) = stream.read(&mut buffer) {}
+    stream.set_nonblocking(false)?;
+    let message = "All workers are busy, try again later";
+    Response::error(503, "busy", message).write_to(&mut stream)
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// reads a request, or the response rejecting it
+fn read_request(stream: &TcpStream) -> io::Result<Result<Request, Response>> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut words = request_line.split_whitespace();
+    let (method, path) = match (words.next(), words.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Ok(Err(Response::error(400, "request", "Invalid request line"))),
+    };
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').unwrap_or((&header, ""));
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            length = match value.parse() {
+                Ok(length) => length,
+                Err(_) => return Ok(Err(Response::error(400, "request", "Invalid length"))),
+            };
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            let message = "Chunked bodies are not supported, send a Content-Length";
+            return Ok(Err(Response::error(411, "request", message)));
+        }
+    }
+    if length > MAX_BODY {
+        let message = format!("The body is larger than {} bytes", MAX_BODY);
+        return Ok(Err(Response::error(413, "request", &message)));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Ok(Request { method, path, body }))
+}
+
+fn handle(mut stream: TcpStream, days: &[Day], timeout: Option<Duration>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let (response, solving) = match read_request(&stream)? {
+        Ok(request) => route(days, timeout, &request.method, &request.path, &request.body),
+        Err(response) => (response, None),
+    };
+    let written = response.write_to(&mut stream);
+    // the worker stays busy until a timed out solution is done, so there are
+    // never more solutions running than workers
+    if let Some(solving) = solving {
+        solving.finish();
+    }
+    written
+}
+
+/// answers a single request:
+///
+/// - `GET /days` lists the numbers of the registered days
+/// - `POST /day/{n}` solves both parts of day n on the input in the body
+pub fn respond(
+    days: &[Day],
+    timeout: Option<Duration>,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> Response {
+    let (response, solving) = route(days, timeout, method, path, body);
+    if let Some(solving) = solving {
+        solving.finish();
+    }
+    response
+}
+
+/// the response, and the solution still running if it timed out
+fn route(
+    days: &[Day],
+    timeout: Option<Duration>,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> (Response, Option<Solving>) {
+    let path = path.split('?').next().unwrap_or_default();
+    match (method, path) {
+        ("GET", "/days") => {
+            let numbers: Vec<String> = days.iter().map(|day| day.number.to_string()).collect();
+            let body = format!(r#"{{"days":[{}]}}"#, numbers.join(","));
+            (Response::json(200, body), None)
+        }
+        (_, "/days") => (
+            Response::error(405, "method", "Use GET to list the days"),
+            None,
+        ),
+        (method, path) if path.starts_with("/day/") => {
+            let number = &path["/day/".len()..];
+            let day = number
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| days.iter().find(|day| day.number == number));
+            let response = match (method, day, std::str::from_utf8(body)) {
+                (_, None, _) => {
+                    let message = format!("Day {} is not registered", number);
+                    Response::error(404, "not_found", &message)
+                }
+                ("POST", Some(day), Ok(input)) => return solve(day, timeout, input),
+                ("POST", _, Err(_)) => Response::error(400, "input", "The input is not UTF-8"),
+                _ => Response::error(405, "method", "Use POST to send an input"),
+            };
+            (response, None)
+        }
+        _ => {
+            let message = format!("There is nothing at {}", path);
+            (Response::error(404, "not_found", &message), None)
+        }
+    }
+}
+
+/// solves both parts, errors of a single part are part of a successful response
+fn solve(day: &Day, timeout: Option<Duration>, input: &str) -> (Response, Option<Solving>) {
+    let options = RunOptions {
+        timing: Timing::Once,
+        ..RunOptions::default()
+    };
+    let solving = Solving::start(day, &options, normalize(input));
+    let response = match solving.wait(timeout) {
+        Ok((parse, parts)) => {
+            let parts: Vec<String> = parts.iter().map(part_json).collect();
+            let body = format!(
+                r#"{{"day":{},"parse":{{"time_us":{}}},"parts":[{}]}}"#,
+                day.number,
+                micros(&parse),
+                parts.join(",")
+            );
+            Response::json(200, body)
+        }
+        Err(err) => {
+            let status = match err {
+                AocError::Parse { .. } => 422,
+                AocError::Timeout { .. } => 504,
+                _ => 500,
+            };
+            let body = format!(r#"{{"day":{},"error":{}}}"#, day.number, error_json(&err));
+            Response::json(status, body)
+        }
+    };
+    (response, Some(solving))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{respond, Server, QUEUED_PER_WORKER};
+    use crate::{fixtures::fixture, registry, solution::Day};
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        thread,
+        time::Duration,
+    };
+
+    fn days() -> Vec<Day> {
+        registry().iter().copied().collect()
+    }
+
+    #[test]
+    fn routes() {
+        let days = days();
+        let response = respond(&days, None, "GET", "/days", b"");
+        assert_eq!(response.status, 200);
+        assert!(response.body.starts_with(r#"{"days":[1,2,3,"#));
+        assert_eq!(respond(&days, None, "POST", "/days", b"").status, 405);
+        assert_eq!(respond(&days, None, "GET", "/day/1", b"").status, 405);
+        assert_eq!(respond(&days, None, "POST", "/day/26", b"").status, 404);
+        assert_eq!(respond(&days, None, "POST", "/day/x", b"").status, 404);
+        assert_eq!(respond(&days, None, "GET", "/", b"").status, 404);
+        assert_eq!(respond(&days, None, "POST", "/day/1", b"\xff").status, 400);
+    }
+
+    #[test]
+    fn solve_and_report_errors() {
+        let days = days();
+        let input = fixture(6).unwrap().input.as_bytes();
+        let response = respond(&days, None, "POST", "/day/6", input);
+        assert_eq!(response.status, 200);
+        assert!(response.body.starts_with(r#"{"day":6,"parse":{"time_us":"#));
+        assert!(response
+            .body
+            .contains(r#"{"part":1,"answer":"5934","time_us":"#));
+        assert!(response
+            .body
+            .contains(r#"{"part":2,"answer":"26984457539","#));
+
+        let response = respond(&days, None, "POST", "/day/6", b"3,4,x");
+        assert_eq!(response.status, 422);
+        assert_eq!(
+            response.body,
+            r#"{"day":6,"error":{"kind":"parse","message":"Expected a number","line":1,"column":5}}"#
+        );
+
+        // day 15 is not finished, its parts fail on their own
+        let response = respond(&days, None, "POST", "/day/15", b"116\n138\n213");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains(r#""error":{"kind":"panic","#));
+    }
+
+    /// sends a raw request to the server and returns the raw response
+    fn request(server: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(server).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serve_on_localhost() {
+        let server = Server::bind(&registry(), 0, None, 2).unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let response = request(address, "GET /days HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"days\":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16]}"));
+
+        let input = "16,1,2,0,4,2,7,1,2,14\n";
+        let response = request(
+            address,
+            &format!(
+                "POST /day/7 HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                input.len(),
+                input
+            ),
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#"{"part":1,"answer":"37","#));
+        assert!(response.contains(r#"{"part":2,"answer":"168","#));
+
+        let response = request(
+            address,
+            "POST /day/7 HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+    }
+
+    #[test]
+    fn refuse_connections_past_the_queue() {
+        let server = Server::bind(&registry(), 0, None, 1).unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+        // connections that never send a request keep the worker and the queue busy
+        let mut stalled = Vec::new();
+        let refused = loop {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_millis(200)))
+                .unwrap();
+            let mut response = String::new();
+            if stream.read_to_string(&mut response).is_ok() {
+                break response;
+            }
+            stalled.push(stream);
+            assert!(
+                stalled.len() <= 1 + QUEUED_PER_WORKER,
+                "nothing was refused"
+            );
+        };
+        assert_eq!(stalled.len(), 1 + QUEUED_PER_WORKER);
+        assert!(refused.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(refused.contains(r#""kind":"busy""#));
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use crate::{error::AocError, helper::OutputFormatter, visualize::Visualize};
+
+/// A solution for a single day of the calendar.
+///
+/// `parse` converts the raw puzzle input into whatever representation
+/// the day works on, `part1` and `part2` then compute the answers from it
+/// and return them instead of printing them directly.
+///
+/// Invalid input results in an error instead of a panic.
+///
+/// Solutions have to be `Send + Sync`, so both parts can be solved in parallel.
+pub trait Solution: Send + Sync {
+    fn parse(input: &str) -> Result<Self, AocError>
+    where
+        Self: Sized;
+    fn part1(&self) -> Result<OutputFormatter, AocError>;
+    fn part2(&self) -> Result<OutputFormatter, AocError>;
+    /// the step by step view of the day for `--visualize`, if it has one
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        None
+    }
+}
+
+/// A registered day, holding its number and a way to construct the solution
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: usize,
+    parser: fn(&str) -> Result<Box<dyn Solution>, AocError>,
+}
+
+impl Day {
+    /// parses the input, a panic while parsing is returned as an error
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Solution>, AocError> {
+        catch_panic(|| (self.parser)(input)).map_err(|err| err.in_day(self.number))
+    }
+    /// runs the given part of an already parsed solution,
+    /// a panic (like an unfinished `todo!()`) is returned as an error
+    pub fn solve(&self, solution: &dyn Solution, part: u8) -> Result<OutputFormatter, AocError> {
+        let result = catch_panic(|| match part {
+            1 => solution.part1(),
+            2 => solution.part2(),
+            _ => Err(AocError::solve(format!("there is no part {}", part))),
+        });
+        result.map_err(|err| err.in_day(self.number))
+    }
+}
+
+thread_local! {
+    /// whether panics on this thread are caught by `catch_panic`
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    /// message and location of the last panic caught on this thread
+    static CAUGHT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown cause".into(),
+    }
+}
+
+/// runs `f`, turning a panic into an error keeping the panic message.
+///
+/// Caught panics are not printed by the panic hook, all other panics are.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, AocError>) -> Result<T, AocError> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                return default_hook(info);
+            }
+            let mut message = panic_message(info.payload());
+            if let Some(location) = info.location() {
+                message = format!("{} at {}", message, location);
+            }
+            CAUGHT.with(|caught| *caught.borrow_mut() = Some(message));
+        }));
+    });
+    let was_catching = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(was_catching));
+    result.unwrap_or_else(|payload| {
+        let message = CAUGHT
+            .with(|caught| caught.borrow_mut().take())
+            .unwrap_or_else(|| panic_message(payload.as_ref()));
+        Err(AocError::Panic { day: None, message })
+    })
+}
+
+fn parse_boxed<S: Solution + 'static>(input: &str) -> Result<Box<dyn Solution>, AocError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+/// All the days that can be run, ordered by their number
+#[derive(Default)]
+pub struct Registry {
+    days: Vec<Day>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// adds a day to the registry, replacing any solution that was
+    /// already registered for the same day
+    pub fn register<S: Solution + 'static>(mut self, number: usize) -> Self {
+        self.days.retain(|day| day.number != number);
+        self.days.push(Day {
+            number,
+            parser: parse_boxed::<S>,
+        });
+        self.days.sort_by_key(|day| day.number);
+        self
+    }
+    pub fn get(&self, number: usize) -> Option<&Day> {
+        self.days.iter().find(|day| day.number == number)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Day> {
+        self.days.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.days.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Registry, Solution};
+    use crate::{
+        error::AocError,
+        helper::{out, OutputFormatter},
+    };
+
+    struct Dummy(usize);
+    impl Solution for Dummy {
+        fn parse(input: &str) -> Result<Self, AocError> {
+            match input.is_empty() {
+                true => Err(AocError::invalid("empty input")),
+                false => Ok(Dummy(input.len())),
+            }
+        }
+        fn part1(&self) -> Result<OutputFormatter, AocError> {
+            Ok(out(1).var("len", self.0))
+        }
+        fn part2(&self) -> Result<OutputFormatter, AocError> {
+            Ok(out(2).var("double len", 2 * self.0))
+        }
+    }
+
+    #[test]
+    fn registry_is_ordered_and_unique() {
+        let registry = Registry::new()
+            .register::<Dummy>(3)
+            .register::<Dummy>(1)
+            .register::<Dummy>(3);
+        let numbers: Vec<usize> = registry.iter().map(|day| day.number).collect();
+        assert_eq!(numbers, vec![1, 3]);
+        assert!(registry.get(2).is_none());
+        assert!(registry.get(3).is_some());
+    }
+
+    struct Unfinished;
+    impl Solution for Unfinished {
+        fn parse(_: &str) -> Result<Self, AocError> {
+            Ok(Unfinished)
+        }
+        fn part1(&self) -> Result<OutputFormatter, AocError> {
+            Ok(out(1).answer("answer", 42))
+        }
+        fn part2(&self) -> Result<OutputFormatter, AocError> {
+            todo!()
+        }
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let registry = Registry::new().register::<Unfinished>(15);
+        let day = registry.get(15).unwrap();
+        let solution = day.parse("").unwrap();
+        assert!(day.solve(solution.as_ref(), 1).is_ok());
+        match day.solve(solution.as_ref(), 2) {
+            Err(AocError::Panic { day, message }) => {
+                assert_eq!(day, Some(15));
+                assert!(message.starts_with("not yet implemented at src/solution.rs:"));
+            }
+            _ => panic!("Expected the panic to be caught"),
+        }
+    }
+
+    #[test]
+    fn errors_carry_the_day() {
+        let registry = Registry::new().register::<Dummy>(7);
+        let day = registry.get(7).unwrap();
+        let err = day.parse("").err().unwrap();
+        assert_eq!(err.day(), Some(7));
+        let solution = day.parse("abc").unwrap();
+        assert!(day.solve(solution.as_ref(), 3).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    str::FromStr,
+};
+
+use crossterm::{
+    cursor::{Hide, Show},
+    queue,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Environment variable disabling colors when set to a non-empty value,
+/// see https://no-color.org
+pub const NO_COLOR_ENV: &str = "NO_COLOR";
+/// Width used when the width of the terminal is unknown
+pub const DEFAULT_WIDTH: usize = 70;
+const MIN_WIDTH: usize = 20;
+
+/// When to color the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// only if stdout is a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(choice: &str) -> Result<Self, Self::Err> {
+        match choice {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("Invalid color choice: '{}'", choice)),
+        }
+    }
+}
+
+impl ColorChoice {
+    /// decides whether to color, given whether `NO_COLOR` is set and stdout is a terminal
+    pub fn use_color(&self, no_color: bool, terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => !no_color && terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    /// enables or disables the colors of everything printed through `colored`
+    pub fn apply(&self) {
+        let no_color = env::var_os(NO_COLOR_ENV).is_some_and(|value| !value.is_empty());
+        let terminal = io::stdout().is_terminal();
+        colored::control::set_override(self.use_color(no_color, terminal));
+    }
+}
+
+/// the width of the terminal stdout is connected to, falling back to
+/// `$COLUMNS` and `DEFAULT_WIDTH` when it is not a terminal
+pub fn width() -> usize {
+    let width = terminal_size::terminal_size()
+        .map(|(width, _)| width.0 as usize)
+        .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH);
+    width.max(MIN_WIDTH)
+}
+
+/// Switches the terminal to a raw alternate screen until it is dropped
+pub struct FullScreen;
+
+impl FullScreen {
+    pub fn enter() -> io::Result<FullScreen> {
+        terminal::enable_raw_mode()?;
+        queue!(io::stdout(), EnterAlternateScreen, Hide)?;
+        io::stdout().flush()?;
+        Ok(FullScreen)
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        let _ = queue!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = io::stdout().flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorChoice;
+
+    #[test]
+    fn color_choice() {
+        assert!(ColorChoice::Auto.use_color(false, true));
+        assert!(!ColorChoice::Auto.use_color(true, true));
+        assert!(!ColorChoice::Auto.use_color(false, false));
+        assert!(ColorChoice::Always.use_color(true, false));
+        assert!(!ColorChoice::Never.use_color(false, true));
+        assert_eq!("never".parse(), Ok(ColorChoice::Never));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// How the runner measures the solutions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    Off,
+    /// measure every step once
+    Once,
+    /// repeat every step the given number of times
+    Bench(usize),
+}
+
+impl Timing {
+    pub fn runs(&self) -> usize {
+        match self {
+            Timing::Off | Timing::Once => 1,
+            Timing::Bench(runs) => *runs,
+        }
+    }
+}
+
+/// The durations of repeated runs of the same step
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Samples {
+    durations: Vec<Duration>,
+}
+
+impl Samples {
+    pub fn new(mut durations: Vec<Duration>) -> Samples {
+        durations.sort();
+        Samples { durations }
+    }
+    pub fn min(&self) -> Duration {
+        self.durations.first().copied().unwrap_or_default()
+    }
+    pub fn max(&self) -> Duration {
+        self.durations.last().copied().unwrap_or_default()
+    }
+    pub fn median(&self) -> Duration {
+        let n = self.durations.len();
+        match n {
+            0 => Duration::ZERO,
+            _ if n % 2 == 1 => self.durations[n / 2],
+            _ => (self.durations[n / 2 - 1] + self.durations[n / 2]) / 2,
+        }
+    }
+}
+
+impl fmt::Display for Samples {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.durations.len() {
+            0 | 1 => write!(f, "{:.2?}", self.median()),
+            _ => write!(
+                f,
+                "min {:.2?}, median {:.2?}, max {:.2?}",
+                self.min(),
+                self.median(),
+                self.max()
+            ),
+        }
+    }
+}
+
+/// runs `step` `runs` times (at least once) and returns the result of the
+/// last run together with the durations
+pub fn measure<T>(runs: usize, mut step: impl FnMut() -> T) -> (T, Samples) {
+    let mut durations = Vec::with_capacity(runs);
+    loop {
+        let start = Instant::now();
+        let result = step();
+        durations.push(start.elapsed());
+        if durations.len() >= runs {
+            return (result, Samples::new(durations));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure, Samples};
+    use std::time::Duration;
+
+    #[test]
+    fn statistics() {
+        let ms = Duration::from_millis;
+        let samples = Samples::new(vec![ms(5), ms(1), ms(3), ms(9)]);
+        assert_eq!(samples.min(), ms(1));
+        assert_eq!(samples.median(), ms(4));
+        assert_eq!(samples.max(), ms(9));
+        assert_eq!(Samples::new(vec![ms(2), ms(7), ms(1)]).median(), ms(2));
+    }
+
+    #[test]
+    fn measure_repeats() {
+        let mut calls = 0;
+        let (result, samples) = measure(3, || {
+            calls += 1;
+            calls
+        });
+        assert_eq!((result, samples.durations.len()), (3, 3));
+        assert_eq!(measure(0, || ()).1.durations.len(), 1);
+    }
+}
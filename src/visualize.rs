@@ -0,0 +1,269 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, Clear, ClearType},
+};
+
+use crate::{
+    grid::{Grid, Pos},
+    terminal::FullScreen,
+};
+
+/// How a cell of a frame stands out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Highlight {
+    #[default]
+    None,
+    /// the cells the current step acts on
+    Focus,
+    /// the cells found by earlier steps
+    Marked,
+}
+
+/// A single cell of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: char,
+    pub highlight: Highlight,
+}
+
+impl Cell {
+    pub fn new(symbol: char) -> Cell {
+        Cell {
+            symbol,
+            highlight: Highlight::None,
+        }
+    }
+}
+
+/// The state of a simulation after one step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// describes the step, like "step 3, 17 flashes"
+    pub caption: String,
+    pub cells: Grid<Cell>,
+}
+
+impl Frame {
+    pub fn new(caption: impl Into<String>, cells: Grid<Cell>) -> Frame {
+        Frame {
+            caption: caption.into(),
+            cells,
+        }
+    }
+
+    /// positions of all cells with the given highlight
+    pub fn highlighted(&self, highlight: Highlight) -> impl Iterator<Item = Pos> + '_ {
+        let cells = self.cells.iter();
+        cells.filter_map(move |(pos, cell)| (cell.highlight == highlight).then_some(pos))
+    }
+}
+
+/// the caption, then the symbols of the cells without highlights
+impl Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.caption)?;
+        for row in self.cells.rows() {
+            let row: String = row.iter().map(|cell| cell.symbol).collect();
+            write!(f, "\n{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+/// A solution whose computation can be watched step by step.
+///
+/// The frames are produced lazily, so simulations may yield many of them.
+pub trait Visualize {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_>;
+}
+
+const MIN_FPS: u32 = 1;
+const MAX_FPS: u32 = 64;
+const DEFAULT_FPS: u32 = 8;
+/// how often keys are checked while paused or after the last frame
+const TICK: Duration = Duration::from_millis(100);
+
+/// The state of the animation controlled by the keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Player {
+    fps: u32,
+    paused: bool,
+}
+
+/// what a key press asks for besides changing the speed or pausing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Step,
+    Quit,
+}
+
+impl Player {
+    fn control(&mut self, key: KeyEvent) -> Option<Control> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        match key.code {
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            // stepping pauses, so the next frame stays on screen
+            KeyCode::Right | KeyCode::Char('n') => {
+                self.paused = true;
+                return Some(Control::Step);
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.fps = (self.fps * 2).min(MAX_FPS),
+            KeyCode::Char('-') => self.fps = (self.fps / 2).max(MIN_FPS),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Control::Quit)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Control::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1) / self.fps
+    }
+
+    fn status(&self, frame: usize, done: bool) -> String {
+        let state = match (done, self.paused) {
+            (true, _) => "done",
+            (false, true) => "paused",
+            (false, false) => "playing",
+        };
+        format!(
+            "frame {}, {}, {} fps   space pause   →/n step   +/- speed   q quit",
+            frame, state, self.fps
+        )
+    }
+}
+
+/// draws the part of `frame` fitting on the screen, above the status line
+fn draw(frame: &Frame, status: &str) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let (width, rows) = (width as usize, (height as usize).saturating_sub(2));
+    let mut stdout = io::stdout();
+    queue!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+    let mut caption = frame.caption.clone();
+    if frame.cells.width() > width || frame.cells.height() > rows {
+        caption += &format!(
+            " (showing {}x{} of {}x{})",
+            frame.cells.width().min(width),
+            frame.cells.height().min(rows),
+            frame.cells.width(),
+            frame.cells.height()
+        );
+    }
+    queue!(stdout, Print(caption.bold()))?;
+    for (y, row) in frame.cells.rows().take(rows).enumerate() {
+        queue!(stdout, MoveTo(0, y as u16 + 1))?;
+        // runs of equally highlighted cells are printed at once
+        for run in row[..row.len().min(width)].chunk_by(|a, b| a.highlight == b.highlight) {
+            let symbols: String = run.iter().map(|cell| cell.symbol).collect();
+            match run[0].highlight {
+                Highlight::None => queue!(stdout, Print(symbols))?,
+                Highlight::Focus => queue!(stdout, Print(symbols.yellow().bold()))?,
+                Highlight::Marked => queue!(stdout, Print(symbols.cyan()))?,
+            }
+        }
+    }
+    queue!(stdout, MoveTo(0, height - 1), Print(status.dim()))?;
+    stdout.flush()
+}
+
+/// Animates the frames of `visualization` full-screen until `q` is pressed
+pub fn play(visualization: &dyn Visualize) -> io::Result<()> {
+    let mut frames = visualization.frames().peekable();
+    let Some(mut frame) = frames.next() else {
+        return Ok(());
+    };
+    let mut player = Player {
+        fps: DEFAULT_FPS,
+        paused: false,
+    };
+    let mut shown = 1;
+    let mut next_at = Instant::now() + player.interval();
+    let _screen = FullScreen::enter()?;
+    loop {
+        let done = frames.peek().is_none();
+        draw(&frame, &player.status(shown, done))?;
+        let wait = match player.paused || done {
+            true => TICK,
+            false => next_at.saturating_duration_since(Instant::now()),
+        };
+        let mut step = false;
+        if event::poll(wait)? {
+            if let Event::Key(key) = event::read()? {
+                match player.control(key) {
+                    Some(Control::Step) => step = true,
+                    Some(Control::Quit) => return Ok(()),
+                    None => {}
+                }
+            }
+        }
+        if !player.paused && Instant::now() >= next_at {
+            step = true;
+        }
+        if step {
+            if let Some(next) = frames.next() {
+                frame = next;
+                shown += 1;
+            }
+            next_at = Instant::now() + player.interval();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cell, Control, Frame, Highlight, Player, MAX_FPS};
+    use crate::grid::Grid;
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn frames_show_caption_and_symbols() {
+        let mut cells = Grid::new(2, 2, Cell::new('.'));
+        cells[(1, 0)] = Cell {
+            symbol: '#',
+            highlight: Highlight::Focus,
+        };
+        let frame = Frame::new("step 1", cells);
+        assert_eq!(frame.to_string(), "step 1\n.#\n..");
+        assert_eq!(
+            frame.highlighted(Highlight::Focus).collect::<Vec<_>>(),
+            [(1, 0)]
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let mut player = Player {
+            fps: 8,
+            paused: false,
+        };
+        let press = |player: &mut Player, code| player.control(KeyEvent::from(code));
+        assert_eq!(press(&mut player, KeyCode::Char(' ')), None);
+        assert!(player.paused);
+        assert_eq!(press(&mut player, KeyCode::Char(' ')), None);
+        assert!(!player.paused);
+        assert_eq!(press(&mut player, KeyCode::Right), Some(Control::Step));
+        assert!(player.paused);
+        for _ in 0..10 {
+            press(&mut player, KeyCode::Char('+'));
+        }
+        assert_eq!(player.fps, MAX_FPS);
+        for _ in 0..10 {
+            press(&mut player, KeyCode::Char('-'));
+        }
+        assert_eq!(player.fps, 1);
+        assert_eq!(press(&mut player, KeyCode::Char('q')), Some(Control::Quit));
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the watched files are checked for changes
+pub const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// what is known about a file to notice changes, `None` if it does not exist
+type Fingerprint = Option<(SystemTime, u64)>;
+
+/// Polls a set of files for changes of their modification time or size
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    fingerprints: Vec<Fingerprint>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Watcher {
+        let fingerprints = paths.iter().map(|path| fingerprint(path)).collect();
+        Watcher {
+            paths,
+            fingerprints,
+        }
+    }
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+    /// whether any file changed, was created or was deleted since the last call
+    pub fn changed(&mut self) -> bool {
+        let fingerprints: Vec<Fingerprint> = self.paths.iter().map(|p| fingerprint(p)).collect();
+        let changed = fingerprints != self.fingerprints;
+        self.fingerprints = fingerprints;
+        changed
+    }
+}
+
+fn fingerprint(path: &Path) -> Fingerprint {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// clears the terminal, on anything else the runs are just separated by a blank line
+fn clear_screen() {
+    match io::stdout().is_terminal() {
+        true => print!("\x1b[2J\x1b[H"),
+        false => println!(),
+    }
+    let _ = io::stdout().flush();
+}
+
+/// calls `run` now and every time one of the watched files changes, never returns
+pub fn watch(mut watcher: Watcher, mut run: impl FnMut()) -> ! {
+    loop {
+        clear_screen();
+        run();
+        let paths: Vec<String> = watcher
+            .paths()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        println!("\nWatching {} (Ctrl+C to stop)", paths.join(", "));
+        while !watcher.changed() {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Watcher;
+    use std::{env, fs, process};
+
+    #[test]
+    fn notices_changes() {
+        let path = env::temp_dir().join(format!("aoc-watch-{}.txt", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut watcher = Watcher::new(vec![path.clone()]);
+        assert!(!watcher.changed());
+        fs::write(&path, "1").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        // the size changes even if the modification time has a coarse resolution
+        fs::write(&path, "1\n2").unwrap();
+        assert!(watcher.changed());
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+    }
+}